@@ -131,19 +131,142 @@ impl RantMap {
     self.map.get(key)
   }
 
+  /// Gets a copy of the value at the specified key, searching the prototype chain if the map doesn't have the key itself.
+  pub fn get(&self, key: &str) -> Option<RantValue> {
+    if let Some(val) = self.raw_get(key) {
+      return Some(val.clone())
+    }
+
+    // Keep track of visited prototypes so cyclic chains don't loop forever
+    let mut visited: Vec<RantMapRef> = vec![];
+    let mut next_proto = self.proto();
+    while let Some(proto) = next_proto {
+      if visited.iter().any(|m| Rc::ptr_eq(m, &proto)) {
+        break
+      }
+      let proto_ref = proto.borrow();
+      if let Some(val) = proto_ref.raw_get(key) {
+        return Some(val.clone())
+      }
+      next_proto = proto_ref.proto();
+      drop(proto_ref);
+      visited.push(proto);
+    }
+    None
+  }
+
   #[inline]
   pub fn raw_has_key(&self, key: &str) -> bool {
     self.map.contains_key(key)
   }
 
+  #[inline]
+  pub fn raw_values(&self) -> RantList {
+    RantList::from_iter(self.map.values().cloned())
+  }
+
+  /// Returns a copy of the map with all of its values deep-copied.
+  /// The prototype is shared with the original.
+  pub fn deep_copy(&self) -> Self {
+    Self {
+      map: self.map.iter().map(|(k, v)| (k.clone(), v.deep_copy())).collect(),
+      proto: self.proto.clone(),
+    }
+  }
+
   #[inline]
   pub fn raw_keys(&self) -> RantList {
     RantList::from_iter(self.map.keys().map(|k| RantValue::String(k.to_string())))
   }
+
+  /// Iterates over the map's own key-value pairs in arbitrary order.
+  #[inline]
+  pub fn raw_pairs(&self) -> impl Iterator<Item = (&str, &RantValue)> {
+    self.map.iter().map(|(k, v)| (k.as_str(), v))
+  }
 }
 
 impl Default for RantMap {
   fn default() -> Self {
     RantMap::new()
   }
+}
+
+/// Tracks the lists and maps on the current path of a traversal through nested collections,
+/// so that traversals can detect when a collection (directly or indirectly) contains itself.
+///
+/// Collections are identified by the address of their shared `Rc`, so a collection that merely appears
+/// more than once in a structure without containing itself is not considered a cycle.
+#[derive(Debug, Default)]
+pub(crate) struct CycleGuard {
+  path: Vec<*const ()>,
+}
+
+impl CycleGuard {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Gets the identity of a list or map value, or `None` for any other value.
+  pub fn identity_of(value: &RantValue) -> Option<*const ()> {
+    match value {
+      RantValue::List(list) => Some(Rc::as_ptr(list) as *const ()),
+      RantValue::Map(map) => Some(Rc::as_ptr(map) as *const ()),
+      _ => None,
+    }
+  }
+
+  /// Adds a collection to the current path. Returns `false` without adding it if it's already on the path, which means it's part of a cycle.
+  pub fn enter(&mut self, identity: *const ()) -> bool {
+    if self.path.contains(&identity) {
+      return false
+    }
+    self.path.push(identity);
+    true
+  }
+
+  /// Removes the most recently entered collection from the current path.
+  pub fn exit(&mut self) {
+    self.path.pop();
+  }
+}
+
+/// Gets the values directly contained in a list or map (excluding prototypes), or `None` for any other value.
+fn child_values(value: &RantValue) -> Option<Vec<RantValue>> {
+  match value {
+    RantValue::List(list) => Some(list.borrow().to_vec()),
+    RantValue::Map(map) => Some(map.borrow().map.values().cloned().collect()),
+    _ => None,
+  }
+}
+
+/// Checks if a value is a list or map that contains itself, either directly or through nested collections.
+/// Prototypes are not followed.
+pub(crate) fn has_cycle(value: &RantValue) -> bool {
+  let mut guard = CycleGuard::new();
+  let (identity, children) = match (CycleGuard::identity_of(value), child_values(value)) {
+    (Some(identity), Some(children)) => (identity, children),
+    _ => return false,
+  };
+  guard.enter(identity);
+
+  // Use an explicit stack of remaining children so that deeply nested collections can't overflow the native stack
+  let mut stack = vec![children.into_iter()];
+  while let Some(children) = stack.last_mut() {
+    match children.next() {
+      Some(child) => {
+        if let (Some(identity), Some(grandchildren)) = (CycleGuard::identity_of(&child), child_values(&child)) {
+          if !guard.enter(identity) {
+            return true
+          }
+          stack.push(grandchildren.into_iter());
+        }
+      },
+      None => {
+        stack.pop();
+        guard.exit();
+      }
+    }
+  }
+  false
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 use logos::*;
 use crate::RantString;
 
-#[derive(Logos, Debug, PartialEq)]
+#[derive(Logos, Debug, PartialEq, Clone)]
 pub enum RantToken {
   #[error]
   #[regex(r"[\w\-_]+")]
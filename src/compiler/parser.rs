@@ -50,6 +50,10 @@ enum SequenceParseMode {
   ///
   /// Breaks on `RightAngle` and `Semi`.
   AccessorFallbackValue,
+  /// Parse a sequence like a parameter default value.
+  ///
+  /// Breaks on `Semi` and `RightBracket`.
+  ParamDefaultValue,
   /// Parse a sequence like a collection initializer element.
   ///
   /// Breaks on `Semi` and `RightParen`.
@@ -102,6 +106,10 @@ enum SequenceEndType {
   AccessorFallbackValueToEnd,
   /// Accessor fallback value was terminated by `Semi`.
   AccessorFallbackValueToDelim,
+  /// Parameter default value was terminated by `Semi`.
+  ParamDefaultValueToDelim,
+  /// Parameter default value was terminated by `RightBracket`.
+  ParamDefaultValueToEnd,
   /// Collection initializer was terminated by `RightParen`.
   CollectionInitEnd,
   /// Collection initializer was termianted by `Semi`.
@@ -473,6 +481,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
           match mode {
             SequenceParseMode::AnonFunctionExpr => return Ok((sequence.with_name_str("anonymous function expression"), SequenceEndType::AnonFunctionExprNoArgs, true)),
             SequenceParseMode::FunctionArg => return Ok((sequence.with_name_str("argument"), SequenceEndType::FunctionArgEndBreak, true)),
+            SequenceParseMode::ParamDefaultValue => return Ok((sequence.with_name_str("default value"), SequenceEndType::ParamDefaultValueToEnd, true)),
             _ => unexpected_token_error!()
           }
         }),
@@ -600,6 +609,8 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
             SequenceParseMode::VariableAssignment => return Ok((sequence.with_name_str("variable assignment"), SequenceEndType::VariableAssignDelim, true)),
             // Accessor fallback value
             SequenceParseMode::AccessorFallbackValue => return Ok((sequence.with_name_str("fallback value"), SequenceEndType::AccessorFallbackValueToDelim, true)),
+            // Parameter default value
+            SequenceParseMode::ParamDefaultValue => return Ok((sequence.with_name_str("default value"), SequenceEndType::ParamDefaultValueToDelim, true)),
             // If we're anywhere else, just print the semicolon like normal text
             _ => seq_add!(Rst::Fragment(RantString::from(";")))
           }
@@ -806,15 +817,39 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                 // Soft error on bad varity order
                 self.syntax_error(Problem::InvalidParamOrder(last_varity.to_string(), varity.to_string()), &full_param_span);
               }
+
+              // Optional parameters can have a default value
+              self.reader.skip_ws();
+              let default_value_expr = if matches!(varity, Varity::Optional) && self.reader.eat_where(|t| matches!(t, Some((RantToken::Equals, _)))) {
+                self.reader.skip_ws();
+                let (default_value, end_type, ..) = self.parse_sequence(SequenceParseMode::ParamDefaultValue)?;
+                Some((Rc::new(default_value), end_type))
+              } else {
+                None
+              };
               
               // Add parameter to list
               params.push(Parameter {
                 name: param_name,
-                varity
+                varity,
+                default_value_expr: default_value_expr.as_ref().map(|(expr, _)| Rc::clone(expr)),
               });
               
               last_varity = varity;
               is_sig_variadic |= is_param_variadic;
+
+              // If a default value was read, its terminator tells us what comes next
+              if let Some((_, end_type)) = default_value_expr {
+                match end_type {
+                  SequenceEndType::ParamDefaultValueToDelim => continue 'read_params,
+                  SequenceEndType::ParamDefaultValueToEnd => break 'read_params,
+                  SequenceEndType::ProgramEnd => {
+                    self.syntax_error(Problem::UnclosedFunctionSignature, start_span);
+                    return Err(())
+                  },
+                  _ => unreachable!()
+                }
+              }
                 
               // Check if there are more params or if the signature is done
               match self.reader.next_solid() {
@@ -932,7 +967,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
               if self.reader.eat_where(|t| matches!(t, Some((RantToken::ComposeValue, ..)))) {
                 if is_composing  {
                   if let Some(compose) = composed_func.take() {
-                    func_args.push(Rc::new(Sequence::one(compose, &self.info)));
+                    func_args.push(Rc::new(ArgumentExpr::new(Rc::new(Sequence::one(compose, &self.info)))));
                   } else {
                     // If take() fails, it means the compose value was already used
                     // No need to push an arg since it won't be used anyway
@@ -959,9 +994,34 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
                   self.syntax_error(Problem::NothingToCompose, &self.reader.last_token_span());
                 }
               } else {
+                // Check for spread marker; only `*` directly followed by a variable access or list is a spread, so text like `*bold*` is unaffected
+                let is_spread = matches!(self.reader.peek(), Some((RantToken::Star, _)))
+                  && matches!(self.reader.peek_second(), Some(RantToken::LeftAngle) | Some(RantToken::LeftParen));
+                if is_spread {
+                  self.reader.skip_one();
+                }
+                // Check for named argument (`name = value`)
+                let name = if !is_spread 
+                  && matches!(self.reader.peek(), Some((RantToken::Fragment, _))) 
+                  && matches!(self.reader.peek_second(), Some(RantToken::Equals)) 
+                {
+                  let (_, name_span) = self.reader.next().unwrap();
+                  let name = Identifier::new(self.reader.last_token_string());
+                  if !is_valid_ident(name.as_str()) {
+                    self.syntax_error(Problem::InvalidIdentifier(name.to_string()), &name_span);
+                  }
+                  self.reader.skip_one();
+                  Some(name)
+                } else {
+                  None
+                };
                 // Parse normal argument
                 let (arg_seq, arg_end, _) = self.parse_sequence(SequenceParseMode::FunctionArg)?;
-                func_args.push(Rc::new(arg_seq));
+                func_args.push(Rc::new(ArgumentExpr {
+                  expr: Rc::new(arg_seq),
+                  is_spread,
+                  name,
+                }));
                 match arg_end {
                   SequenceEndType::FunctionArgEndNext => continue,
                   SequenceEndType::FunctionArgEndBreak => {
@@ -987,7 +1047,7 @@ impl<'source, 'report, R: Reporter> RantParser<'source, 'report, R> {
           () => {
             // If the composition value wasn't used, insert it as the first argument
             if let Some(compose) = composed_func.take() {
-              func_args.insert(0, Rc::new(Sequence::one(compose, &self.info)));
+              func_args.insert(0, Rc::new(ArgumentExpr::new(Rc::new(Sequence::one(compose, &self.info)))));
             }
           }
         }
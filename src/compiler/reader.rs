@@ -76,6 +76,12 @@ impl<'source> RantTokenReader<'source> {
     self.lexer.span()
   }
   
+  /// Returns the token after the next one without consuming either of them.
+  pub fn peek_second(&mut self) -> Option<RantToken> {
+    self.peek();
+    self.lexer.clone().next()
+  }
+  
   /// Returns a reference to the next token without consuming it.
   pub fn peek(&mut self) -> Option<&(RantToken, Range<usize>)> {
     // If a peek was already performed, return a reference to it
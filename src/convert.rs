@@ -373,7 +373,8 @@ impl<T: FromRant> FromRantArgs for T {
 
     let param = Parameter {
       name: Identifier::new(RantString::from("arg0")),
-      varity
+      varity,
+      default_value_expr: None,
     };
 
     vec![param]
@@ -438,6 +439,7 @@ macro_rules! impl_from_rant_args {
         vec![$(Parameter { 
           name: Identifier::new(RantString::from(format!("arg{}", inc(&mut i)))),
           varity: as_varity::<$generic_types>(),
+          default_value_expr: None,
         },)*]
       }
     }
@@ -460,10 +462,12 @@ macro_rules! impl_from_rant_args {
         vec![$(Parameter { 
           name: Identifier::new(RantString::from(format!("arg{}", inc(&mut i)))),
           varity: as_varity::<$generic_types>(),
+          default_value_expr: None,
         },)*
         Parameter {
           name: Identifier::new(RantString::from(format!("arg{}", inc(&mut i)))),
           varity: Varity::VariadicStar,
+          default_value_expr: None,
         }]
       }
     }
@@ -486,10 +490,12 @@ macro_rules! impl_from_rant_args {
         vec![$(Parameter { 
           name: Identifier::new(RantString::from(format!("arg{}", inc(&mut i)))),
           varity: as_varity::<$generic_types>(),
+          default_value_expr: None,
         },)*
         Parameter {
           name: Identifier::new(RantString::from(format!("arg{}", inc(&mut i)))),
           varity: Varity::VariadicPlus,
+          default_value_expr: None,
         }]
       }
     }
@@ -316,12 +316,14 @@ impl Display for Varity {
 }
 
 /// Describes a function parameter.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Parameter {
   /// The name of the parameter
   pub name: Identifier,
   /// The varity of the parameter
   pub varity: Varity,
+  /// The expression that provides the value of the parameter when its argument is omitted (optional parameters only)
+  pub default_value_expr: Option<Rc<Sequence>>,
 }
 
 impl Parameter {
@@ -333,12 +335,35 @@ impl Parameter {
   }
 }
 
+/// Describes a function argument expression.
+#[derive(Debug)]
+pub struct ArgumentExpr {
+  /// The expression that produces the argument value
+  pub expr: Rc<Sequence>,
+  /// If true, a list produced by the expression is expanded into separate arguments
+  pub is_spread: bool,
+  /// The name of the parameter the argument is passed to, if it's a named argument
+  pub name: Option<Identifier>,
+}
+
+impl ArgumentExpr {
+  /// Creates a regular (non-spread) argument from an expression.
+  #[inline]
+  pub fn new(expr: Rc<Sequence>) -> Self {
+    Self {
+      expr,
+      is_spread: false,
+      name: None,
+    }
+  }
+}
+
 /// Describes a function call.
 #[derive(Debug)]
 pub struct FunctionCall {
   pub flag: PrintFlag,
   pub id: Rc<AccessPath>,
-  pub arguments: Rc<Vec<Rc<Sequence>>>,
+  pub arguments: Rc<Vec<Rc<ArgumentExpr>>>,
 }
 
 /// Describes a function definition.
@@ -363,7 +388,7 @@ pub struct ClosureExpr {
 pub struct AnonFunctionCall {
   pub flag: PrintFlag,
   pub expr: Rc<Sequence>,
-  pub args: Rc<Vec<Rc<Sequence>>>,
+  pub args: Rc<Vec<Rc<ArgumentExpr>>>,
 }
 
 /// Key creation methods for map initializer entries.
@@ -40,12 +40,17 @@ pub use collections::*;
 pub use convert::*;
 pub use value::*;
 pub use var::*;
+pub use runtime::format::OutputWhitespacePolicy;
+pub use runtime::{RuntimeError, RuntimeErrorType};
+pub use runtime::{RantProfile, ProfileKey, ProfiledElement};
 
 use crate::compiler::CompilerMessage;
 use crate::lang::Sequence;
 use crate::compiler::{RantCompiler, Reporter, ErrorKind as CompilerErrorKind};
 use crate::runtime::*;
-use crate::random::RantRng;
+use crate::runtime::resolver::{CustomSelectorMode, SelectorMode};
+
+pub use crate::random::RantRng;
 
 use std::{path::Path, rc::Rc, cell::RefCell, fmt::Display, path::PathBuf, io::ErrorKind, collections::HashMap};
 use std::env;
@@ -83,6 +88,8 @@ pub struct Rant {
   debug_mode: bool,
   globals: HashMap<RantString, RantVar, FnvBuildHasher>,
   options: RantOptions,
+  last_profile: Option<RantProfile>,
+  selector_modes: HashMap<RantString, Rc<CustomSelectorMode>, FnvBuildHasher>,
 }
 
 impl Rant {
@@ -114,6 +121,8 @@ impl Rant {
       globals: Default::default(),
       rng: Rc::new(RantRng::new(options.seed)),
       options,
+      last_profile: None,
+      selector_modes: Default::default(),
     };
 
     // Load standard library
@@ -251,6 +260,26 @@ impl Rant {
     self.globals.keys().map(|k| k.as_str())
   }
   
+  /// Registers a custom selector mode under the specified name, making it available to `[mksel]` and `[sel]`.
+  ///
+  /// The `select` function receives the number of elements in the block, the RNG, and the index it returned last time for the same selector (if any);
+  /// it must return the index of the next element to resolve. Returning an out-of-range index raises a selector error at runtime.
+  ///
+  /// Built-in mode names take precedence over custom ones. Registering a mode under an existing custom name replaces it.
+  pub fn register_selector_mode<F>(&mut self, name: &str, select: F) 
+  where F: Fn(usize, &RantRng, Option<usize>) -> usize + 'static 
+  {
+    self.selector_modes.insert(RantString::from(name), Rc::new(CustomSelectorMode {
+      name: name.to_owned(),
+      select: Rc::new(select),
+    }));
+  }
+
+  /// Gets the selector mode with the specified name, including custom modes.
+  pub(crate) fn get_selector_mode(&self, name: &str) -> Option<SelectorMode> {
+    SelectorMode::from_name(name).or_else(|| self.selector_modes.get(name).map(|custom| SelectorMode::Custom(Rc::clone(custom))))
+  }
+  
   /// Gets the current RNG seed.
   pub fn seed(&self) -> u64 {
     self.rng.seed()
@@ -267,6 +296,14 @@ impl Rant {
     self.rng = Rc::new(RantRng::new(seed));
   }
   
+  /// Gets the profiling counters collected during the last program run.
+  ///
+  /// Returns `None` if profiling is disabled via `RantOptions` or no program has run yet.
+  #[inline]
+  pub fn last_profile(&self) -> Option<&RantProfile> {
+    self.last_profile.as_ref()
+  }
+
   /// Runs a program and returns the output value.
   pub fn run(&mut self, program: &RantProgram) -> RuntimeResult<RantValue> {
     VM::new(self.rng.clone(), self, program).run()
@@ -418,6 +455,16 @@ pub struct RantOptions {
   /// Specifies a preferred module loading path with higher precedence than the global module path.
   /// If not specified, looks in the current working directory.
   pub local_modules_path: Option<String>,
+  /// Specifies how whitespace in the output of blocks and function bodies is post-processed. Defaults to `Verbatim`.
+  pub whitespace_policy: OutputWhitespacePolicy,
+  /// The maximum number of values the runtime's value stack can hold before raising a value stack overflow error.
+  /// This is separate from the call stack limit.
+  pub value_stack_limit: usize,
+  /// Enables counting how many times each block and function call is resolved. Defaults to `false`.
+  /// The counters for the most recent run are available via `Rant::last_profile()`.
+  pub enable_profiling: bool,
+  /// A read-only map of host-provided values that programs can read with `[env]`. Defaults to an empty map.
+  pub environment: RantMap,
 }
 
 impl Default for RantOptions {
@@ -429,6 +476,10 @@ impl Default for RantOptions {
       enable_require: true,
       enable_global_modules: true,
       local_modules_path: None,
+      whitespace_policy: Default::default(),
+      value_stack_limit: DEFAULT_VALUE_STACK_LIMIT,
+      enable_profiling: false,
+      environment: RantMap::new(),
     }
   }
 }
@@ -1,14 +1,14 @@
 use rand::Rng;
 use rand_xoshiro::rand_core::SeedableRng;
 use rand_xoshiro::Xoshiro256PlusPlus;
-use std::{cell::RefCell, hash::Hasher};
+use std::{cell::{Cell, RefCell}, hash::Hasher};
 use fnv::FnvHasher;
 use crate::util::*;
 
 /// Rant's random number generator, which is a thin wrapper around a xoshiro256++ PRNG.
 #[derive(Debug)]
 pub struct RantRng {
-  seed: u64,
+  seed: Cell<u64>,
   rng: RefCell<Xoshiro256PlusPlus>,
 }
 
@@ -16,7 +16,7 @@ impl RantRng {
   /// Creates a new RNG with the supplied seed.
   pub fn new(seed: u64) -> Self {
     Self {
-      seed,
+      seed: Cell::new(seed),
       rng: RefCell::new(Xoshiro256PlusPlus::seed_from_u64(seed))
     }
   }
@@ -25,7 +25,7 @@ impl RantRng {
   /// Uses the Fowler-Noll-Vo hash function.
   pub fn fork_u64(&self, seed: u64) -> Self {
     let mut hasher = FnvHasher::default();
-    hasher.write_u64(self.seed);
+    hasher.write_u64(self.seed.get());
     hasher.write_u64(seed);
     RantRng::new(hasher.finish())
   }
@@ -34,7 +34,7 @@ impl RantRng {
   /// Uses the Fowler-Noll-Vo hash function.
   pub fn fork_i64(&self, seed: i64) -> Self {
     let mut hasher = FnvHasher::default();
-    hasher.write_u64(self.seed);
+    hasher.write_u64(self.seed.get());
     hasher.write_i64(seed);
     RantRng::new(hasher.finish())
   }
@@ -43,7 +43,7 @@ impl RantRng {
   /// Uses the Fowler-Noll-Vo hash function.
   pub fn fork_str(&self, seed: &str) -> Self {
     let mut hasher = FnvHasher::default();
-    hasher.write_u64(self.seed);
+    hasher.write_u64(self.seed.get());
     hasher.write(seed.as_bytes());
     RantRng::new(hasher.finish())
   }
@@ -52,16 +52,39 @@ impl RantRng {
   /// Uses the Fowler-Noll-Vo hash function.
   pub fn fork_random(&self) -> Self {
     let mut hasher = FnvHasher::default();
-    hasher.write_u64(self.seed);
+    hasher.write_u64(self.seed.get());
     hasher.write_u64(self.rng.borrow_mut().gen());
     RantRng::new(hasher.finish())
   }
 }
 
+/// A snapshot of the internal state of a `RantRng`.
+#[derive(Debug, Clone)]
+pub struct RantRngState(Xoshiro256PlusPlus);
+
 impl RantRng {
   /// Gets the current seed of the RNG.
   pub fn seed(&self) -> u64 {
-    self.seed
+    self.seed.get()
+  }
+
+  /// Reinitializes the RNG in place with the supplied seed, as if it had been created with `RantRng::new(seed)`.
+  pub fn reseed(&self, seed: u64) {
+    self.seed.set(seed);
+    *self.rng.borrow_mut() = Xoshiro256PlusPlus::seed_from_u64(seed);
+  }
+
+  /// Takes a snapshot of the RNG's current internal state.
+  #[inline]
+  pub fn save_state(&self) -> RantRngState {
+    RantRngState(self.rng.borrow().clone())
+  }
+
+  /// Restores the RNG's internal state from a snapshot, so that it produces the same values it did after the snapshot was taken.
+  /// The seed is not affected.
+  #[inline]
+  pub fn restore_state(&self, state: &RantRngState) {
+    *self.rng.borrow_mut() = state.0.clone();
   }
   
   /// Generates a pseudorandom `i64` between two inclusive values. The range may be specified in either order.
@@ -80,6 +103,12 @@ impl RantRng {
     self.rng.borrow_mut().gen_range(min, max)
   }
   
+  /// Generates a pseudorandom `u64` over the entire range of the type.
+  #[inline]
+  pub fn next_u64(&self) -> u64 {
+    self.rng.borrow_mut().gen()
+  }
+  
   /// Generates a pseudorandom `usize` between 0 and `max` (exclusive).
   #[inline]
   pub fn next_usize(&self, max: usize) -> usize {
@@ -97,4 +126,15 @@ impl RantRng {
   pub fn next_bool(&self, p: f64) -> bool {
     self.rng.borrow_mut().gen_bool(saturate(p))
   }
+
+  /// Shuffles a slice in place using a Fisher-Yates shuffle.
+  ///
+  /// Exactly `n - 1` values are drawn from the RNG for a slice of length `n`,
+  /// so the resulting permutation only depends on the RNG state when the shuffle begins.
+  #[inline]
+  pub fn shuffle<T>(&self, slice: &mut [T]) {
+    for i in (1..slice.len()).rev() {
+      slice.swap(i, self.next_usize(i + 1));
+    }
+  }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 pub use output::*;
+pub use profile::*;
 pub use stack::*;
 use crate::*;
 use crate::lang::*;
@@ -9,11 +10,13 @@ use smallvec::{SmallVec, smallvec};
 pub(crate) mod format;
 pub(crate) mod resolver;
 mod output;
+mod profile;
 mod stack;
 
 pub type RuntimeResult<T> = Result<T, RuntimeError>;
 
 pub const MAX_STACK_SIZE: usize = 20000;
+pub const DEFAULT_VALUE_STACK_LIMIT: usize = 20000;
 pub(crate) const CALL_STACK_INLINE_COUNT: usize = 4;
 pub(crate) const VALUE_STACK_INLINE_COUNT: usize = 4;
 
@@ -23,12 +26,16 @@ pub struct VM<'rant> {
   program: &'rant RantProgram,
   val_stack: SmallVec<[RantValue; VALUE_STACK_INLINE_COUNT]>,
   call_stack: CallStack,
-  resolver: Resolver
+  resolver: Resolver,
+  val_stack_limit: usize,
+  profile: Option<RantProfile>,
 }
 
 impl<'rant> VM<'rant> {
   #[inline]
   pub fn new(rng: Rc<RantRng>, engine: &'rant mut Rant, program: &'rant RantProgram) -> Self {
+    let val_stack_limit = engine.options.value_stack_limit;
+    let profile = if engine.options.enable_profiling { Some(Default::default()) } else { None };
     Self {
       resolver: Resolver::new(&rng),
       rng_stack: smallvec![rng],
@@ -36,6 +43,8 @@ impl<'rant> VM<'rant> {
       program,
       val_stack: Default::default(),
       call_stack: Default::default(),
+      val_stack_limit,
+      profile,
     }
   }
 }
@@ -83,7 +92,8 @@ pub enum Intent {
   /// Pop `expr_count` values off the stack and use them for expression fields in a setter.
   SetValue { path: Rc<AccessPath>, auto_def: bool, expr_count: usize },
   /// Evaluate `arg_exprs` in order, then pop the argument values off the stack, pop a function off the stack, and pass the arguments to the function.
-  Invoke { arg_exprs: Rc<Vec<Rc<Sequence>>>, eval_count: usize, flag: PrintFlag },
+  /// Values of spread arguments that are lists are expanded into separate arguments, and named arguments are matched to parameters by name.
+  Invoke { arg_exprs: Rc<Vec<Rc<ArgumentExpr>>>, eval_count: usize, flag: PrintFlag },
   /// Pop `argc` args off the stack, then pop a function off the stack and call it with the args.
   Call { argc: usize, flag: PrintFlag, override_print: bool },
   /// Pop value from stack and add it to a list. If `index` is out of range, print the list.
@@ -122,6 +132,7 @@ impl<'rant> VM<'rant> {
     if let Err(err) = result.as_mut() {
       err.stack_trace = Some(self.call_stack.gen_stack_trace());
     }
+    self.engine.last_profile = self.profile.take();
     result
   }
 
@@ -140,6 +151,7 @@ impl<'rant> VM<'rant> {
     if let Err(err) = result.as_mut() {
       err.stack_trace = Some(self.call_stack.gen_stack_trace());
     }
+    self.engine.last_profile = self.profile.take();
     result
   }
   
@@ -148,6 +160,10 @@ impl<'rant> VM<'rant> {
     // Push the program's root sequence onto the call stack
     // This doesn't need an overflow check because it will *always* succeed
     self.push_frame_unchecked(self.program.root.clone(), true, StackFrameFlavor::FunctionBody);
+
+    // Apply the engine's whitespace policy to the root output so that all child outputs inherit it
+    let ws_policy = self.engine.options.whitespace_policy;
+    self.cur_frame_mut().use_output_mut(|output| output.format_mut().ws_policy = ws_policy);
     
     // Run whatever is on the top of the call stack
     'from_the_top: 
@@ -208,15 +224,21 @@ impl<'rant> VM<'rant> {
           Intent::Invoke { arg_exprs, eval_count, flag } => {
             // First, evaluate all arguments
             if eval_count < arg_exprs.len() {
-              let arg_expr = Rc::clone(arg_exprs.get(arg_exprs.len() - eval_count - 1).unwrap());
+              let arg_expr = Rc::clone(&arg_exprs.get(arg_exprs.len() - eval_count - 1).unwrap().expr);
               self.cur_frame_mut().push_intent_front(Intent::Invoke { arg_exprs, eval_count: eval_count + 1, flag });
               self.push_frame_flavored(arg_expr, true, StackFrameFlavor::ArgumentExpression)?;
               continue 'from_the_top;
             } else {
-              // Pop the evaluated args off the stack
+              // Pop the evaluated args off the stack, expanding any spread lists
               let mut args = vec![];
-              for _ in 0..arg_exprs.len() {
-                args.push(self.pop_val()?);
+              let mut named_args = vec![];
+              for arg_expr in arg_exprs.iter() {
+                match (self.pop_val()?, &arg_expr.name) {
+                  (arg, Some(name)) => named_args.push((name, arg)),
+                  (RantValue::List(list), None) if arg_expr.is_spread => args.extend(list.borrow().iter().cloned()),
+                  (other, None) if arg_expr.is_spread => runtime_error!(RuntimeErrorType::ArgumentError, format!("cannot spread '{}' value; expected a list", other.type_name())),
+                  (arg, None) => args.push(arg),
+                }
               }
 
               // Pop the function and make sure it's callable
@@ -227,6 +249,11 @@ impl<'rant> VM<'rant> {
                 other => runtime_error!(RuntimeErrorType::CannotInvokeValue, format!("cannot invoke '{}' value", other.type_name()))
               };
 
+              // Match any named args to the function's parameters
+              if !named_args.is_empty() {
+                args = Self::bind_named_args(&func, args, named_args)?;
+              }
+
               // Call the function
               self.call_func(func, args, flag, false)?;
               continue 'from_the_top;
@@ -579,6 +606,51 @@ impl<'rant> VM<'rant> {
     Ok(self.pop_val().unwrap_or_default())
   }
 
+  /// Combines positional and named arguments into a single positional argument list for `func`.
+  /// Positional arguments fill parameters from the start; parameters that receive no argument are left empty.
+  fn bind_named_args(func: &RantFunction, positional_args: Vec<RantValue>, named_args: Vec<(&Identifier, RantValue)>) -> RuntimeResult<Vec<RantValue>> {
+    let positional_count = positional_args.len();
+    let mut args: Vec<Option<RantValue>> = positional_args.into_iter().map(Some).collect();
+    
+    for (name, value) in named_args {
+      let param_index = match func.params.iter().position(|param| param.name == *name) {
+        Some(index) => index,
+        None => runtime_error!(RuntimeErrorType::ArgumentError, format!("function has no parameter named '{}'; quote the argument (as in \"{}=...\") to pass it as text", name, name)),
+      };
+
+      if func.params[param_index].varity.is_variadic() {
+        runtime_error!(RuntimeErrorType::ArgumentError, format!("variadic parameter '{}' cannot be passed by name", name));
+      }
+
+      if args.len() <= param_index {
+        args.resize(param_index + 1, None);
+      }
+
+      if args[param_index].is_some() {
+        runtime_error!(RuntimeErrorType::ArgumentError, format!("parameter '{}' was passed more than once", name));
+      }
+
+      args[param_index] = Some(value);
+    }
+
+    // Make sure no required parameters were skipped
+    if let Some(missing_param) = func.params.iter()
+      .enumerate()
+      .skip(positional_count)
+      .find(|(i, param)| param.is_required() && !param.varity.is_variadic() && !matches!(args.get(*i), Some(Some(_))))
+      .map(|(_, param)| param)
+    {
+      runtime_error!(RuntimeErrorType::ArgumentMismatch, format!("missing argument for required parameter '{}'", missing_param.name));
+    }
+
+    // Variadic functions need all parameters before the variadic one to be filled
+    if func.is_variadic() && args.len() < func.vararg_start_index {
+      args.resize(func.vararg_start_index, None);
+    }
+
+    Ok(args.into_iter().map(Option::unwrap_or_default).collect())
+  }
+
   #[inline]
   fn call_func(&mut self, func: RantFunctionRef, mut args: Vec<RantValue>, flag: PrintFlag, override_print: bool) -> RuntimeResult<()> {
     let argc = args.len();
@@ -607,6 +679,8 @@ impl<'rant> VM<'rant> {
       args
     };
 
+    self.profile_hit(ProfiledElement::FunctionCall);
+
     // Tell frame to print output if it's available
     if is_printing && !override_print {
       self.cur_frame_mut().push_intent_front(Intent::PrintValue);
@@ -622,15 +696,23 @@ impl<'rant> VM<'rant> {
         // Push the function onto the call stack
         self.push_frame_flavored(Rc::clone(user_func), is_printing, StackFrameFlavor::FunctionBody)?;
 
-        // Pass the args to the function scope
+        // Pass the args to the function scope; omitted or empty args with default values are evaluated later
         let mut args = args.drain(..);
+        let mut pending_defaults = vec![];
         for param in func.params.iter() {
-          self.call_stack.def_var_value(
-            self.engine, 
-            param.name.as_str(), 
-            AccessPathKind::Local, 
-            args.next().unwrap_or(RantValue::Empty)
-          )?;
+          match (&param.default_value_expr, args.next().unwrap_or(RantValue::Empty)) {
+            (Some(default_value_expr), RantValue::Empty) => {
+              pending_defaults.push((param.name.clone(), Rc::clone(default_value_expr)));
+            },
+            (_, arg) => {
+              self.call_stack.def_var_value(
+                self.engine, 
+                param.name.as_str(), 
+                AccessPathKind::Local, 
+                arg
+              )?;
+            }
+          }
         }
 
         // Pass captured vars to the function scope
@@ -642,6 +724,15 @@ impl<'rant> VM<'rant> {
             RantVar::clone(capture_var)
           )?;
         }
+
+        // Evaluate default values inside the function scope, in parameter order
+        for (param_name, default_value_expr) in pending_defaults.into_iter().rev() {
+          self.cur_frame_mut().push_intent_front(Intent::DefVar { vname: param_name, access_kind: AccessPathKind::Local });
+          self.cur_frame_mut().push_intent_front(Intent::RuntimeCall(Box::new(move |vm| {
+            vm.push_frame(default_value_expr, true)?;
+            Ok(())
+          })));
+        }
       },
     }
     Ok(())
@@ -772,14 +863,14 @@ impl<'rant> VM<'rant> {
     };
 
     // Evaluate the rest of the path
-    for accessor in path_iter {
+    let mut path_iter = path_iter.peekable();
+    while let Some(accessor) = path_iter.next() {
+      // Functions called from the end of a path can be methods
+      let is_method_lookup = prefer_function && path_iter.peek().is_none();
       match accessor {
         // Static key
         AccessPathComponent::Name(key) => {
-          getter_value = match getter_value.key_get(key.as_str()) {
-            Ok(val) => val,
-            Err(err) => runtime_error!(RuntimeErrorType::KeyError(err))
-          };
+          getter_value = Self::get_member(getter_value, key.as_str(), is_method_lookup)?;
         },
         // Index
         AccessPathComponent::Index(index) => {
@@ -799,10 +890,7 @@ impl<'rant> VM<'rant> {
               }
             },
             _ => {
-              getter_value = match getter_value.key_get(key.to_string().as_str()) {
-                Ok(val) => val,
-                Err(err) => runtime_error!(RuntimeErrorType::KeyError(err))
-              };
+              getter_value = Self::get_member(getter_value, key.to_string().as_str(), is_method_lookup)?;
             }
           }
         },
@@ -822,10 +910,33 @@ impl<'rant> VM<'rant> {
     Ok(())
   }
 
+  /// Gets the value at `key` in `target`.
+  /// If `is_method_lookup` is true and `key` resolves to a function inherited from a map's prototype, the function is bound to the map as its first argument.
+  #[inline]
+  fn get_member(target: RantValue, key: &str, is_method_lookup: bool) -> RuntimeResult<RantValue> {
+    if is_method_lookup {
+      if let RantValue::Map(map) = &target {
+        let method = {
+          let map = map.borrow();
+          if map.raw_has_key(key) { None } else { map.get(key) }
+        };
+        if let Some(RantValue::Function(func)) = method {
+          return Ok(RantValue::Function(Rc::new(RantFunction::bind(target, func))))
+        }
+      }
+    }
+
+    match target.key_get(key) {
+      Ok(val) => Ok(val),
+      Err(err) => runtime_error!(RuntimeErrorType::KeyError(err))
+    }
+  }
+
   /// Checks for an active block and attempts to iterate it. If a valid element is returned, it is pushed onto the call stack.
   pub(crate) fn check_block(&mut self) -> RuntimeResult<()> {
     let mut is_printing = false;
     let mut is_repeater = false;
+    let mut map_output = None;
 
     let rng = self.rng_clone();
     
@@ -837,6 +948,7 @@ impl<'rant> VM<'rant> {
       if let Some(element) = state.next_element(rng.as_ref()).into_runtime_result()? {
         // Figure out if the block is supposed to print anything
         is_printing = !state.flag().is_sink();
+        map_output = state.map_output().cloned();
         Some(element)
       } else {
         // If the block is done, pop the state from the block stack
@@ -856,14 +968,24 @@ impl<'rant> VM<'rant> {
 
       match element {
         BlockAction::Element(elem_seq) => {
-          // Combine with no_print to determine if we *should* print anything, or just push the result to the stack
-          if is_printing {
+          let use_output = is_printing || map_output.is_some();
+          if let Some(map_func) = map_output {
+            // Pass the element output to the mapping function, which prints the result in place of the original output
+            self.cur_frame_mut().push_intent_front(Intent::RuntimeCall(Box::new(move |vm| {
+              let output = vm.pop_val()?;
+              vm.push_val(RantValue::Function(map_func))?;
+              vm.push_val(output)?;
+              vm.cur_frame_mut().push_intent_front(Intent::Call { argc: 1, flag: if is_printing { PrintFlag::Hint } else { PrintFlag::Sink }, override_print: false });
+              Ok(())
+            })));
+          } else if is_printing {
+            // Combine with no_print to determine if we *should* print anything, or just push the result to the stack
             self.cur_frame_mut().push_intent_front(Intent::PrintValue);
           }
           // Push the next element
           self.push_frame_flavored(
             Rc::clone(&elem_seq), 
-            is_printing, 
+            use_output, 
             if is_repeater { 
               StackFrameFlavor::RepeaterElement 
             } else { 
@@ -896,6 +1018,15 @@ impl<'rant> VM<'rant> {
 
   #[inline(always)]
   pub(crate) fn push_block(&mut self, block: &Block, flag: PrintFlag) -> RuntimeResult<()> {
+    // If the block output is being captured, resolve the block in its own frame and store the frame's output in a local
+    if let Some(output_var) = self.resolver.attrs_mut().output_var.take() {
+      let block = Block { flag: block.flag, elements: Rc::clone(&block.elements) };
+      self.cur_frame_mut().push_intent_front(Intent::DefVar { vname: output_var, access_kind: AccessPathKind::Local });
+      return self.push_empty_frame(Box::new(move |vm| vm.push_block(&block, flag)), true, StackFrameFlavor::Original)
+    }
+
+    self.profile_hit(ProfiledElement::Block);
+
     // Push a new state onto the block stack
     self.resolver.push_block(block, flag);
 
@@ -906,6 +1037,16 @@ impl<'rant> VM<'rant> {
     Ok(())
   }
 
+  /// Increments the profiling counter for an element at the current frame's position, if profiling is enabled.
+  #[inline(always)]
+  fn profile_hit(&mut self, element: ProfiledElement) {
+    if let Some(profile) = self.profile.as_mut() {
+      if let Some(frame) = self.call_stack.top() {
+        profile.record(element, frame.origin_name(), frame.debug_pos());
+      }
+    }
+  }
+
   #[inline(always)]
   pub(crate) fn set_var_value(&mut self, varname: &str, access: AccessPathKind, val: RantValue) -> RuntimeResult<()> {
     self.call_stack.set_var_value(self.engine, varname, access, val)
@@ -928,11 +1069,11 @@ impl<'rant> VM<'rant> {
 
   #[inline(always)]
   pub(crate) fn push_val(&mut self, val: RantValue) -> RuntimeResult<usize> {
-    if self.val_stack.len() < MAX_STACK_SIZE {
+    if self.val_stack.len() < self.val_stack_limit {
       self.val_stack.push(val);
       Ok(self.val_stack.len())
     } else {
-      runtime_error!(RuntimeErrorType::StackOverflow, "value stack has overflowed");
+      runtime_error!(RuntimeErrorType::ValueStackOverflow, format!("value stack has overflowed (limit: {} values)", self.val_stack_limit));
     }
   }
 
@@ -1062,33 +1203,34 @@ impl<'rant> VM<'rant> {
   pub fn func_return(&mut self, ret_val: Option<RantValue>) -> RuntimeResult<()> {
     if let Some(block_depth) = self.call_stack.taste_for_first(StackFrameFlavor::FunctionBody) {
       // Pop down to owning scope of function
-      if let Some(break_val) = ret_val {
-        for _ in 0..=block_depth {
-          self.pop_frame()?;
+      for i in 0..=block_depth {
+        let mut old_frame = self.pop_frame()?;
+
+        // If a block state is associated with the popped frame, pop that too
+        match old_frame.flavor() {
+          StackFrameFlavor::RepeaterElement | StackFrameFlavor::BlockElement => {
+            self.resolver_mut().pop_block();
+          },
+          _ => {}
         }
-        self.push_val(break_val)?;
-      } else {
-        for i in 0..=block_depth {
-          let mut old_frame = self.pop_frame()?;
 
-          // If a block state is associated with the popped frame, pop that too
-          match old_frame.flavor() {
-            StackFrameFlavor::RepeaterElement | StackFrameFlavor::BlockElement => {
-              self.resolver_mut().pop_block();
-            },
-            _ => {}
-          }
+        // Partial output is discarded if there's a return value; otherwise, it becomes the function's output
+        if ret_val.is_some() {
+          continue
+        }
 
-          // Handle output
-          if let Some(output) = old_frame.render_output_value() {
-            if i < block_depth {
-              self.cur_frame_mut().write_value(output);
-            } else {
-              self.push_val(output)?;
-            }
+        if let Some(output) = old_frame.render_output_value() {
+          if i < block_depth {
+            self.cur_frame_mut().write_value(output);
+          } else {
+            self.push_val(output)?;
           }
         }
       }
+
+      if let Some(ret_val) = ret_val {
+        self.push_val(ret_val)?;
+      }
       
       Ok(())
     } else {
@@ -1096,6 +1238,13 @@ impl<'rant> VM<'rant> {
     }
   }
 
+  /// Returns `true` if a function body other than the program root can be returned from.
+  #[inline]
+  pub fn is_in_function(&self) -> bool {
+    self.call_stack.taste_for_first(StackFrameFlavor::FunctionBody)
+      .map_or(false, |depth| depth + 1 < self.call_stack.len())
+  }
+
   #[inline(always)]
   pub fn cur_frame_mut(&mut self) -> &mut StackFrame {
     self.call_stack.top_mut().unwrap()
@@ -1106,6 +1255,11 @@ impl<'rant> VM<'rant> {
     self.call_stack.top().unwrap()
   }
 
+  #[inline(always)]
+  pub(crate) fn call_stack_depth(&self) -> usize {
+    self.call_stack.len()
+  }
+
   #[inline(always)]
   pub fn rng(&self) -> &RantRng {
     self.rng_stack.last().unwrap().as_ref()
@@ -1194,8 +1348,10 @@ impl Display for RuntimeError {
 /// Provides general categories of runtime errors encountered in Rant.
 #[derive(Debug)]
 pub enum RuntimeErrorType {
-  /// Stack overflow
+  /// Call stack overflow
   StackOverflow,
+  /// Value stack overflow
+  ValueStackOverflow,
   /// Stack underflow
   StackUnderflow,
   /// Variable access error, such as attempting to access a nonexistent variable
@@ -1234,6 +1390,7 @@ impl Display for RuntimeErrorType {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(f, "{}", match self {
       RuntimeErrorType::StackOverflow => "stack overflow",
+      RuntimeErrorType::ValueStackOverflow => "value stack overflow",
       RuntimeErrorType::StackUnderflow => "stack underflow",
       RuntimeErrorType::InvalidAccess => "invalid access",
       RuntimeErrorType::InvalidOperation => "invalid operation",
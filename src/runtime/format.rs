@@ -3,12 +3,14 @@ use crate::RantValue;
 #[derive(Debug, Clone)]
 pub struct OutputFormat {
   pub ws_norm_mode: WhitespaceNormalizationMode,
+  pub ws_policy: OutputWhitespacePolicy,
 }
 
 impl Default for OutputFormat {
   fn default() -> Self {
     Self {
       ws_norm_mode: Default::default(),
+      ws_policy: Default::default(),
     }
   }
 }
@@ -29,4 +31,21 @@ impl Default for WhitespaceNormalizationMode {
   fn default() -> Self {
     Self::Default
   }
+}
+
+/// Controls how whitespace is post-processed in the rendered output of blocks and function bodies.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutputWhitespacePolicy {
+  /// Leaves all output whitespace as-is.
+  Verbatim,
+  /// Collapses each run of whitespace into a single space, or a single line break if the run contains one.
+  Collapse,
+  /// Collapses whitespace like `Collapse`, and additionally trims whitespace from the edges of each block element and function body.
+  Trim,
+}
+
+impl Default for OutputWhitespacePolicy {
+  fn default() -> Self {
+    Self::Verbatim
+  }
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 use crate::{RantValue, RantString};
-use super::format::{WhitespaceNormalizationMode, OutputFormat};
+use super::format::{WhitespaceNormalizationMode, OutputFormat, OutputWhitespacePolicy};
 use std::rc::Rc;
 
 const INITIAL_CHAIN_CAPACITY: usize = 64;
@@ -107,6 +107,43 @@ impl OutputWriter {
   }
 }
 
+impl OutputWriter {
+  /// Renders the output like `render_value`, then applies the output format's whitespace policy if the result is a string.
+  #[inline]
+  pub fn render_value_with_ws_policy(self) -> RantValue {
+    let policy = self.format.ws_policy;
+    match (policy, self.render_value()) {
+      (OutputWhitespacePolicy::Verbatim, val) => val,
+      (OutputWhitespacePolicy::Collapse, RantValue::String(s)) => RantValue::String(collapse_whitespace(s.as_str())),
+      (OutputWhitespacePolicy::Trim, RantValue::String(s)) => RantValue::String(collapse_whitespace(s.trim())),
+      (_, val) => val,
+    }
+  }
+}
+
+/// Collapses each run of whitespace in `s` into a single space, or a single line break if the run contains one.
+fn collapse_whitespace(s: &str) -> String {
+  let mut collapsed = String::with_capacity(s.len());
+  let mut chars = s.chars().peekable();
+  while let Some(c) = chars.next() {
+    if !c.is_whitespace() {
+      collapsed.push(c);
+      continue
+    }
+
+    let mut has_line_break = c == '\n';
+    while let Some(&next) = chars.peek() {
+      if !next.is_whitespace() {
+        break
+      }
+      has_line_break |= next == '\n';
+      chars.next();
+    }
+    collapsed.push(if has_line_break { '\n' } else { ' ' });
+  }
+  collapsed
+}
+
 impl Default for OutputWriter {
   fn default() -> Self {
     OutputWriter::new(Default::default())
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+/// The kind of program element counted by the profiler.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ProfiledElement {
+  /// A block was resolved.
+  Block,
+  /// A function was called.
+  FunctionCall,
+}
+
+/// Identifies a profiled program element by its kind, origin, and source position.
+///
+/// Source positions are only tracked when debug mode is enabled; otherwise, all elements in the same origin share position `(0, 0)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProfileKey {
+  /// The kind of element.
+  pub element: ProfiledElement,
+  /// The path or name of the program containing the element.
+  pub origin: String,
+  /// The line number of the element.
+  pub line: usize,
+  /// The column number of the element.
+  pub col: usize,
+}
+
+/// Resolution counters collected by the runtime while profiling is enabled.
+#[derive(Debug, Clone, Default)]
+pub struct RantProfile {
+  counts: HashMap<ProfileKey, usize>,
+}
+
+impl RantProfile {
+  #[inline]
+  pub(crate) fn record(&mut self, element: ProfiledElement, origin: &str, (line, col): (usize, usize)) {
+    let key = ProfileKey {
+      element,
+      origin: origin.to_owned(),
+      line,
+      col,
+    };
+    *self.counts.entry(key).or_insert(0) += 1;
+  }
+
+  /// Gets the number of times the element with the specified key was resolved.
+  #[inline]
+  pub fn count(&self, key: &ProfileKey) -> usize {
+    self.counts.get(key).copied().unwrap_or(0)
+  }
+
+  /// Gets the total number of times any element of the specified kind was resolved.
+  #[inline]
+  pub fn total(&self, element: ProfiledElement) -> usize {
+    self.counts.iter().filter(|(key, _)| key.element == element).map(|(_, count)| count).sum()
+  }
+
+  /// Iterates over all counters in no particular order.
+  #[inline]
+  pub fn iter(&self) -> impl Iterator<Item = (&ProfileKey, usize)> {
+    self.counts.iter().map(|(key, count)| (key, *count))
+  }
+
+  /// Returns all counters sorted from most to least frequently resolved.
+  pub fn hot_spots(&self) -> Vec<(&ProfileKey, usize)> {
+    let mut counts: Vec<(&ProfileKey, usize)> = self.iter().collect();
+    counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+    counts
+  }
+}
\ No newline at end of file
@@ -1,10 +1,16 @@
 use std::{cell::RefCell, rc::Rc, mem, error::Error, fmt::Display};
-use crate::{random::RantRng, RantValue, lang::{Sequence, Block, PrintFlag}, FromRant, ValueError};
+use crate::{random::RantRng, RantValue, RantFunctionRef, lang::{Sequence, Block, PrintFlag, Identifier}, FromRant, ValueError};
 use smallvec::SmallVec;
+use fnv::FnvHashMap;
 use super::{IntoRuntimeResult, RuntimeError};
 
 pub type SelectorRef = Rc<RefCell<Selector>>;
 
+/// Signature of a custom selection strategy.
+///
+/// Receives the element count, the RNG, and the index the selector returned last time (if any), and returns the index of the next element.
+pub type SelectorFn = dyn Fn(usize, &RantRng, Option<usize>) -> usize;
+
 /// The number of attribute frames you can put on the stack before the runtime goes up in smoke.
 const DEFAULT_MAX_ATTR_FRAMES: usize = 127;
 const BLOCK_STACK_INLINE_COUNT: usize = 4;
@@ -15,6 +21,8 @@ pub struct Resolver {
   base_attrs: AttributeFrame,
   attr_override_stack: Vec<AttributeFrame>,
   block_stack: SmallVec<[BlockState; BLOCK_STACK_INLINE_COUNT]>,
+  /// Last element index chosen by each `[no-double]` block, keyed by the address of its element list
+  no_double_indices: FnvHashMap<usize, usize>,
 }
 
 /// Stores state information for a block that is currently being resolved.
@@ -27,6 +35,8 @@ pub struct BlockState {
   cur_steps: usize,
   total_steps: usize,
   prev_step_separated: bool,
+  /// Index of the previously selected element, if known
+  last_index: Option<usize>,
 }
 
 impl BlockState {
@@ -36,12 +46,18 @@ impl BlockState {
       if self.cur_steps == 0 || self.prev_step_separated {
         self.prev_step_separated = false;
         self.cur_steps += 1;
-        let next_index = self.attrs.selector.as_ref().map_or_else(
-          // Default block selection behavior
-          || Ok(rng.next_usize(self.elements.len())), 
+        let elem_count = self.elements.len();
+        let next_index = match (self.attrs.selector.as_ref(), self.last_index) {
           // Selector behavior
-          |sel| sel.borrow_mut().select(self.elements.len(), rng)
-        )?;
+          (Some(sel), _) => sel.borrow_mut().select(elem_count, &self.attrs.weights, rng)?,
+          // Avoid repeating the previous element
+          (None, Some(last_index)) if self.attrs.no_double && elem_count > 1 && last_index < elem_count => {
+            (last_index + 1 + rng.next_usize(elem_count - 1)) % elem_count
+          },
+          // Default block selection behavior
+          (None, _) => rng.next_usize(elem_count),
+        };
+        self.last_index = Some(next_index);
         Ok(Some(BlockAction::Element(Rc::clone(&self.elements[next_index]))))
       } else {
         self.prev_step_separated = true;
@@ -92,6 +108,12 @@ impl BlockState {
   pub fn flag(&self) -> PrintFlag {
     self.flag
   }
+
+  /// Gets the function that the output of each element is passed through, if any.
+  #[inline]
+  pub fn map_output(&self) -> Option<&RantFunctionRef> {
+    self.attrs.map_output.as_ref()
+  }
 }
 
 pub enum BlockAction {
@@ -140,6 +162,7 @@ impl Resolver {
       base_attrs: Default::default(),
       attr_override_stack: vec![Default::default()],
       block_stack: Default::default(),
+      no_double_indices: Default::default(),
     }
   }
 }
@@ -149,6 +172,11 @@ impl Resolver {
   #[inline]
   pub fn push_block(&mut self, block: &Block, flag: PrintFlag) {
     let attrs = self.take_attrs();
+    let last_index = if attrs.no_double {
+      self.no_double_indices.get(&(Rc::as_ptr(&block.elements) as usize)).copied()
+    } else {
+      None
+    };
     let state = BlockState {
       elements: Rc::clone(&block.elements),
       flag: PrintFlag::prioritize(block.flag, flag),
@@ -157,6 +185,7 @@ impl Resolver {
       attrs,
       prev_step_separated: false,
       force_stop: false,
+      last_index,
     };
     // Since blocks are associated with call stack frames, there is no need to check the stack size here
     self.block_stack.push(state);
@@ -165,7 +194,14 @@ impl Resolver {
   /// Removes the active block state from the block stack.
   #[inline]
   pub fn pop_block(&mut self) -> Option<BlockState> {
-    self.block_stack.pop()
+    let state = self.block_stack.pop()?;
+    // Remember the last element of [no-double] blocks for their next visit
+    if state.attrs.no_double {
+      if let Some(last_index) = state.last_index {
+        self.no_double_indices.insert(Rc::as_ptr(&state.elements) as usize, last_index);
+      }
+    }
+    Some(state)
   }
 
   /// Gets a reference to the active block state.
@@ -267,6 +303,14 @@ pub struct AttributeFrame {
   pub separator: RantValue,
   /// Active selector
   pub selector: Option<SelectorRef>,
+  /// Variable that receives the output of the next block instead of the caller
+  pub output_var: Option<Identifier>,
+  /// Prevents the block from selecting the same element twice in a row, including across visits
+  pub no_double: bool,
+  /// Function applied to the output of each element before it is printed
+  pub map_output: Option<RantFunctionRef>,
+  /// Element weights used by the `weighted` selector mode, in element order
+  pub weights: Vec<f64>,
 }
 
 impl AttributeFrame {
@@ -313,6 +357,10 @@ impl Default for AttributeFrame {
       reps: Reps::Once,
       separator: RantValue::Empty,
       selector: None,
+      output_var: None,
+      no_double: false,
+      map_output: None,
+      weights: vec![],
     }
   }
 }
@@ -329,6 +377,10 @@ pub struct Selector {
   parity: bool,
   /// Jump table used by some selector modes (won't allocate if unused)
   jump_table: Vec<usize>,
+  /// Index returned by the previous selection (used by custom modes)
+  last_index: Option<usize>,
+  /// Number of selections made since initialization (used for progress tracking)
+  steps: usize,
 }
 
 impl Selector {
@@ -340,6 +392,41 @@ impl Selector {
       count: 0,
       parity: false,
       jump_table: Default::default(),
+      last_index: None,
+      steps: 0,
+    }
+  }
+
+  /// Returns the selector to its uninitialized state, so that it is initialized again (e.g. reshuffled) the next time it is used.
+  #[inline]
+  pub fn reset(&mut self) {
+    self.index = 0;
+    self.count = 0;
+    self.parity = false;
+    self.jump_table.clear();
+    self.last_index = None;
+    self.steps = 0;
+  }
+
+  /// Gets how far through its current cycle the selector is, from 0 (nothing selected yet) to 1 (the cycle is complete).
+  ///
+  /// Returns `None` for modes without a finite cycle, such as `random`.
+  pub fn progress(&self) -> Option<f64> {
+    if !self.is_initialized() || self.steps == 0 {
+      return match self.mode {
+        SelectorMode::Random | SelectorMode::One | SelectorMode::NoDouble | SelectorMode::Weighted | SelectorMode::Custom(_) => None,
+        _ => Some(0.0),
+      }
+    }
+
+    let n = self.count;
+    let cycle_progress = |cycle_len: usize| ((self.steps - 1) % cycle_len + 1) as f64 / cycle_len as f64;
+
+    match self.mode {
+      SelectorMode::Forward | SelectorMode::Reverse | SelectorMode::Deck | SelectorMode::DeckLoop => Some(cycle_progress(n)),
+      SelectorMode::ForwardClamp | SelectorMode::ReverseClamp | SelectorMode::DeckClamp => Some(self.steps.min(n) as f64 / n as f64),
+      SelectorMode::Ping | SelectorMode::Pong => Some(cycle_progress((2 * n).saturating_sub(2).max(1))),
+      SelectorMode::Random | SelectorMode::One | SelectorMode::NoDouble | SelectorMode::Weighted | SelectorMode::Custom(_) => None,
     }
   }
 
@@ -375,6 +462,8 @@ impl Selector {
       SelectorMode::NoDouble => {
         self.index = rng.next_usize(elem_count);
       },
+      SelectorMode::Weighted => {},
+      SelectorMode::Custom(_) => {},
     }
 
     Ok(())
@@ -397,7 +486,10 @@ impl Selector {
     }
   }
 
-  pub fn select(&mut self, elem_count: usize, rng: &RantRng) -> Result<usize, SelectorError> {
+  /// Selects the index of the next element.
+  ///
+  /// `weights` holds the weight of each element for the `weighted` mode; elements without a weight default to 1.
+  pub fn select(&mut self, elem_count: usize, weights: &[f64], rng: &RantRng) -> Result<usize, SelectorError> {
     // Initialize and sanity check
     if !self.is_initialized() {
       self.init(rng, elem_count)?;
@@ -409,6 +501,7 @@ impl Selector {
     }
 
     let cur_index = self.index;
+    self.steps += 1;
 
     // Iterate the selector
     match self.mode {
@@ -484,6 +577,37 @@ impl Selector {
           0
         };
       },
+      SelectorMode::Weighted => {
+        let weight_of = |i: usize| weights.get(i).copied().unwrap_or(1.0);
+        let total_weight: f64 = (0..elem_count).map(weight_of).sum();
+        if total_weight <= 0.0 {
+          return Err(SelectorError::ZeroTotalWeight)
+        }
+
+        // Walk the cumulative weights until the random point is passed, skipping zero-weight elements
+        let mut remaining = rng.next_normal_f64() * total_weight;
+        let mut index = 0;
+        for i in 0..elem_count {
+          let weight = weight_of(i);
+          if weight <= 0.0 {
+            continue
+          }
+          index = i;
+          if remaining < weight {
+            break
+          }
+          remaining -= weight;
+        }
+        return Ok(index)
+      },
+      SelectorMode::Custom(ref custom) => {
+        let index = (custom.select)(elem_count, rng, self.last_index);
+        if index >= elem_count {
+          return Err(SelectorError::IndexOutOfRange { index, count: elem_count })
+        }
+        self.last_index = Some(index);
+        return Ok(index)
+      },
     }
 
     Ok(cur_index)
@@ -494,6 +618,8 @@ impl Selector {
 pub enum SelectorError {
   ElementCountMismatch { expected: usize, found: usize },
   InvalidElementCount(usize),
+  IndexOutOfRange { index: usize, count: usize },
+  ZeroTotalWeight,
 }
 
 impl Error for SelectorError {
@@ -511,6 +637,8 @@ impl Display for SelectorError {
     match self {
       SelectorError::ElementCountMismatch { expected, found } => write!(f, "selector expected {} elements, but found {}", expected, found),
       SelectorError::InvalidElementCount(n) => write!(f, "selector does not support blocks of size {}", n),
+      SelectorError::IndexOutOfRange { index, count } => write!(f, "custom selector chose index {} in a block of size {}", index, count),
+      SelectorError::ZeroTotalWeight => write!(f, "weighted selector requires at least one element with a positive weight"),
     }
   }
 }
@@ -552,30 +680,56 @@ pub enum SelectorMode {
   Pong,
   /// Ensures that no one element index is selected twice in a row.
   NoDouble,
+  /// Selects a random element each time, with odds proportional to the element weights set by `[weight]`.
+  Weighted,
+  /// Selects elements using a strategy registered by the host.
+  Custom(Rc<CustomSelectorMode>),
+}
+
+impl SelectorMode {
+  /// Gets the built-in selector mode with the specified name.
+  pub fn from_name(name: &str) -> Option<Self> {
+    Some(match name {
+      "random" =>         SelectorMode::Random,
+      "one" =>            SelectorMode::One,
+      "forward" =>        SelectorMode::Forward,
+      "forward-clamp" =>  SelectorMode::ForwardClamp,
+      "reverse" =>        SelectorMode::Reverse,
+      "reverse-clamp" =>  SelectorMode::ReverseClamp,
+      "deck" =>           SelectorMode::Deck,
+      "deck-loop" =>      SelectorMode::DeckLoop,
+      "deck-clamp" =>     SelectorMode::DeckClamp,
+      "ping" =>           SelectorMode::Ping,
+      "pong" =>           SelectorMode::Pong,
+      "no-double" =>      SelectorMode::NoDouble,
+      "weighted" =>       SelectorMode::Weighted,
+      _ => return None
+    })
+  }
+}
+
+/// A selection strategy registered by the host under a name.
+pub struct CustomSelectorMode {
+  /// The name of the mode
+  pub name: String,
+  /// The function that selects the next element
+  pub select: Rc<SelectorFn>,
+}
+
+impl std::fmt::Debug for CustomSelectorMode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "[custom selector mode '{}']", self.name)
+  }
 }
 
 impl FromRant for SelectorMode {
   fn from_rant(val: RantValue) -> Result<Self, ValueError> {
     match &val {
       RantValue::String(s) => {
-        Ok(match s.as_str() {
-          "random" =>         SelectorMode::Random,
-          "one" =>            SelectorMode::One,
-          "forward" =>        SelectorMode::Forward,
-          "forward-clamp" =>  SelectorMode::ForwardClamp,
-          "reverse" =>        SelectorMode::Reverse,
-          "reverse-clamp" =>  SelectorMode::ReverseClamp,
-          "deck" =>           SelectorMode::Deck,
-          "deck-loop" =>      SelectorMode::DeckLoop,
-          "deck-clamp" =>     SelectorMode::DeckClamp,
-          "ping" =>           SelectorMode::Ping,
-          "pong" =>           SelectorMode::Pong,
-          "no-double" =>      SelectorMode::NoDouble,
-          _ => return Err(ValueError::InvalidConversion {
-            from: val.type_name(),
-            to: "selector mode",
-            message: Some(format!("invalid selector mode: '{}'", s))
-          })
+        SelectorMode::from_name(s.as_str()).ok_or_else(|| ValueError::InvalidConversion {
+          from: val.type_name(),
+          to: "selector mode",
+          message: Some(format!("invalid selector mode: '{}'", s))
         })
       },
       _ => Err(ValueError::InvalidConversion {
@@ -462,9 +462,14 @@ impl StackFrame {
     }
   }
 
+  /// Renders the frame's output, if it has any.
+  /// The whitespace policy is only applied to the output of block elements and function bodies.
   #[inline]
   pub fn render_output_value(&mut self) -> Option<RantValue> {
-    self.output.take().map(|o| o.render_value())
+    let use_ws_policy = matches!(self.flavor, 
+      StackFrameFlavor::BlockElement | StackFrameFlavor::RepeaterElement | StackFrameFlavor::FunctionBody
+    );
+    self.output.take().map(|o| if use_ws_policy { o.render_value_with_ws_policy() } else { o.render_value() })
   }
 }
 
@@ -12,12 +12,14 @@ mod assert;
 mod block;
 mod boolean;
 mod collections;
+mod color;
 mod compare;
 mod control;
 mod convert;
 mod format;
 mod general;
 mod generate;
+mod markov;
 mod math;
 mod proto;
 mod strings;
@@ -25,8 +27,8 @@ mod verify;
 
 use self::{
   assert::*, block::*, boolean::*, collections::*, 
-  compare::*, control::*, convert::*, format::*, 
-  general::*, generate::*, math::*, proto::*, 
+  color::*, compare::*, control::*, convert::*, format::*, 
+  general::*, generate::*, markov::*, math::*, proto::*, 
   strings::*, verify::*
 };
 
@@ -71,17 +73,17 @@ pub(crate) fn load_stdlib(context: &mut Rant)
 
   load_funcs!(
     // General functions
-    alt, call, either, len, get_type as "type", seed, nop, resolve, fork, unfork,
+    alt, call, cap, copy, either, env, len, get_type as "type", memoize, seed, nop, resolve, fork, unfork, rng_save as "rng-save", rng_restore as "rng-restore", stack_depth as "stack-depth", here,
 
     // Assertion functions
-    _assert as "assert", _assert_eq as "assert-eq", _assert_neq as "assert-neq",
+    _assert as "assert", _assert_eq as "assert-eq", _assert_neq as "assert-neq", _assert_type as "assert-type", _assert_approx as "assert-approx",
 
     // Formatting functions
-    whitespace_fmt as "whitespace-fmt",
+    whitespace_fmt as "whitespace-fmt", nl, tab, space, percent, collapse_blanks as "collapse-blanks",
 
     // Block attribute / control flow functions
-    break_ as "break", continue_ as "continue", if_ as "if", else_if as "else-if", else_ as "else", 
-    mksel, rep, return_ as "return", sel, sep,
+    break_ as "break", continue_ as "continue", cond, guard, if_ as "if", else_if as "else-if", else_ as "else", 
+    map_output as "map-output", mksel, no_double as "no-double", rep, return_ as "return", sel, sel_reset as "sel-reset", sel_progress as "sel-progress", sep, set_output as "set-output", weight,
 
     // Attribute frame stack functions
     push_attrs as "push-attrs", pop_attrs as "pop-attrs", count_attrs as "count-attrs", reset_attrs as "reset-attrs",
@@ -97,31 +99,36 @@ pub(crate) fn load_stdlib(context: &mut Rant)
 
     // Verification functions
     is_string as "is-string", is_integer as "is-integer", is_float as "is-float", 
-    is_number as "is-number", is_bool as "is-bool", is_empty as "is-empty", is_nan as "is-nan",
-    is_between as "is-between", is_any as "is-any", is,
+    is_number as "is-number", is_bool as "is-bool", is_empty as "is-empty", is_any as "non-empty", is_nan as "is-nan", is_whole as "is-whole", is_cyclic as "is-cyclic",
+    is_between as "is-between", is_any as "is-any", is, contains, expect_type as "expect-type",
 
     // Math functions
-    add, sub, mul, div, mul_add as "mul-add", mod_ as "mod", neg, recip, is_odd as "is-odd", is_even as "is-even", is_factor as "is-factor",
-    clamp,
+    add, sub, mul, div, fdiv, divmod, mul_add as "mul-add", mod_ as "mod", neg, pow, recip, sqrt, abs, floor, ceil, round, is_odd as "is-odd", is_even as "is-even", is_factor as "is-factor",
+    clamp, clamp01, abs_diff as "abs-diff", distance, remap, snap,
+
+    // Color functions
+    rgb, hex_to_rgb as "hex-to-rgb", lerp_color as "lerp-color",
 
     // Conversion functions
-    to_int as "int", to_float as "float", to_string as "string",
+    to_int as "int", to_float as "float", to_string as "string", num_str as "num-str",
 
     // Generator functions
-    alpha, dig, digh, dignz, maybe, rand, randf, rand_list as "rand-list", randf_list as "randf-list", shred,
+    alpha, alpha_weighted as "alpha-weighted", dig, digh, dignz, gen_name as "gen-name", lorem, maybe, rand, randf, randf_normal as "randf-normal", randf_normal_clamped as "randf-normal-clamped", rand_list as "rand-list", randf_list as "randf-list", random_color as "random-color", range, reseed, shred, uuid, markov,
 
     // Prototype functions
-    proto, set_proto as "set-proto",
+    bind, new, proto, set_proto as "set-proto",
 
     // Collection functions
-    assoc, clear, has, keys, index_of as "index-of", insert, last_index_of as "last-index-of", remove, sift, sifted, squish, squished, take, translate,
+    assoc, clear, clone, deep_merge as "deep-merge", entries, flatten_map as "flatten-map", unflatten_map as "unflatten-map", has, keys, keys_count as "keys-count", values_count as "values-count", index_of as "index-of", insert, last_index_of as "last-index-of", pad_list as "pad-list", remove, sift, sifted, squish, squished, take, translate,
+    sum_values as "sum-values", mean_values as "mean-values", with_key as "with-key", take_key as "take-key",
 
     // List functions
-    pick, filter, join, map, sort, sorted, shuffle, shuffled, sum, min, max,
-    list_push as "push", list_pop as "pop", oxford_join as "oxford-join", zip,
+    pick, pick_deck as "pick-deck", weighted_sample as "weighted-sample", filter, any, all, find, find_index as "find-index", fold, join, map, reverse, sort, sorted, shuffle, shuffled, sum, mean, median, min, max, min_max as "min-max",
+    list_push as "push", list_pop as "pop", oxford_join as "oxford-join", zip, zip_longest as "zip-longest", interleave, gather, flatten, slice, unique, dedup, chunk, windows, with_index as "with-index",
+    sort_indices as "sort-indices", sort_indices_by as "sort-indices-by", group_runs as "group-runs",
 
     // String functions
-    lower, upper, seg, split, lines, indent,
+    lower, upper, seg, split, substring, lines, unlines, count_lines as "count-lines", count_words as "count-words", indent, dedent, trim, trim_start as "trim-start", trim_end as "trim-end", trim_lines as "trim-lines", char_index_map as "char-index-map", count_substr as "count-substr", starts_with as "starts-with", ends_with as "ends-with", matches_glob as "matches-glob", repeat_str as "repeat-str", replace_map as "replace-map", rot, sentences, smart_title as "smart-title", capitalize, title_case as "title-case", template,
 
     // Error functions
     error
@@ -19,4 +19,28 @@ pub(crate) fn _assert_neq(vm: &mut VM, (unexpected, actual, message): (RantValue
     runtime_error!(RuntimeErrorType::AssertError, "{}", message.unwrap_or_else(|| format!("unexpected value: {:?}", unexpected)));
   }
   Ok(())
+}
+
+/// `[$assert-type: value (any); type-name (string); message? (string)]`
+///
+/// Raises an assertion error if the type of `value` is not `type-name`.
+pub(crate) fn _assert_type(vm: &mut VM, (value, type_name, message): (RantValue, String, Option<String>)) -> RantStdResult {
+  if value.type_name() != type_name {
+    runtime_error!(RuntimeErrorType::AssertError, "{}", message.unwrap_or_else(|| format!("expected type: {}; actual type: {} (value: {:?})", type_name, value.type_name(), value)));
+  }
+  Ok(())
+}
+
+/// `[$assert-approx: expected (number); actual (number); epsilon (number); message? (string)]`
+///
+/// Raises an assertion error if `expected` and `actual` differ by more than `epsilon`.
+pub(crate) fn _assert_approx(vm: &mut VM, (expected, actual, epsilon, message): (f64, f64, f64, Option<String>)) -> RantStdResult {
+  if epsilon.is_nan() || epsilon < 0.0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "epsilon must be a non-negative number, but was {}", epsilon);
+  }
+  let diff = (expected - actual).abs();
+  if diff.is_nan() || diff > epsilon {
+    runtime_error!(RuntimeErrorType::AssertError, "{}", message.unwrap_or_else(|| format!("expected: {:?} (± {:?}); actual: {:?}", expected, epsilon, actual)));
+  }
+  Ok(())
 }
\ No newline at end of file
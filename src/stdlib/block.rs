@@ -1,5 +1,6 @@
 use super::*;
-use crate::resolver::{SelectorMode, Reps, Selector};
+use crate::resolver::{Reps, Selector, SelectorRef};
+use crate::lang::{Identifier, is_valid_ident};
 
 pub(crate) fn resolve(vm: &mut VM, value: RantValue) -> RantStdResult {
   if let RantValue::Block(block) = value {
@@ -47,6 +48,48 @@ pub(crate) fn sep(vm: &mut VM, separator: RantValue) -> RantStdResult {
   Ok(())
 }
 
+/// `[$no-double]`
+///
+/// Prevents the next block from selecting the same element twice in a row.
+/// Unlike the `no-double` selector mode, this also applies across separate visits to the block,
+/// so a block resolved repeatedly (e.g. in a loop) never produces the same element back-to-back.
+/// Has no effect on blocks that use a selector.
+pub(crate) fn no_double(vm: &mut VM, _: ()) -> RantStdResult {
+  vm.resolver_mut().attrs_mut().no_double = true;
+  Ok(())
+}
+
+/// `[$map-output: func (function)]`
+///
+/// Passes the output of each element of the next block to `func` and prints the return value instead.
+pub(crate) fn map_output(vm: &mut VM, func: RantFunctionRef) -> RantStdResult {
+  vm.resolver_mut().attrs_mut().map_output = Some(func);
+  Ok(())
+}
+
+/// `[$weight: w (number)]`
+///
+/// Sets the weight of the next element of the next block, starting from the first element; call it once per element, in order.
+/// Weights are only used by the `weighted` selector mode. Elements without a weight default to a weight of 1.
+pub(crate) fn weight(vm: &mut VM, w: f64) -> RantStdResult {
+  if !w.is_finite() || w < 0.0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "element weight must be a non-negative finite number, but got {}", w);
+  }
+  vm.resolver_mut().attrs_mut().weights.push(w);
+  Ok(())
+}
+
+/// `[$set-output: varname (string)]`
+///
+/// Stores the output of the next block in a local variable named `varname` instead of printing it.
+pub(crate) fn set_output(vm: &mut VM, varname: String) -> RantStdResult {
+  if !is_valid_ident(varname.as_str()) {
+    runtime_error!(RuntimeErrorType::ArgumentError, "invalid variable name: '{}'", varname);
+  }
+  vm.resolver_mut().attrs_mut().output_var = Some(Identifier::new(RantString::from(varname)));
+  Ok(())
+}
+
 pub(crate) fn step_index(vm: &mut VM, _: ()) -> RantStdResult {
   let n = vm.resolver().active_block().map_or(0, |block| block.step_index());
   vm.cur_frame_mut().write_value(RantValue::Integer(n as i64));
@@ -65,18 +108,32 @@ pub(crate) fn step_count(vm: &mut VM, _: ()) -> RantStdResult {
   Ok(())
 }
 
-pub(crate) fn mksel(vm: &mut VM, mode: SelectorMode) -> RantStdResult {
-  let selector = Rc::new(RefCell::new(Selector::new(mode)));
+/// Creates a selector from the name of a built-in or custom selector mode.
+fn make_selector(vm: &VM, mode_name: &str) -> Result<SelectorRef, RuntimeError> {
+  match vm.context().get_selector_mode(mode_name) {
+    Some(mode) => Ok(Rc::new(RefCell::new(Selector::new(mode)))),
+    None => runtime_error!(RuntimeErrorType::ArgumentError, "invalid selector mode: '{}'", mode_name),
+  }
+}
+
+pub(crate) fn mksel(vm: &mut VM, mode: String) -> RantStdResult {
+  let selector = make_selector(vm, mode.as_str())?;
   let special = RantSpecial::Selector(selector);
   vm.cur_frame_mut().write_value(RantValue::Special(special));
   Ok(())
 }
 
+/// `[$sel: selector? (selector|string)]`
+///
+/// Sets the selector for the next block. A mode name can be passed instead of a selector to use a new selector with that mode.
 pub(crate) fn sel(vm: &mut VM, selector: Option<RantValue>) -> RantStdResult {
   vm.resolver_mut().attrs_mut().selector = match selector {
     Some(RantValue::Special(RantSpecial::Selector(selector))) => {
       Some(Rc::clone(&selector))
     },
+    Some(RantValue::String(mode)) => {
+      Some(make_selector(vm, mode.as_str())?)
+    },
     Some(val) => {
       return Err(RuntimeError {
         error_type: RuntimeErrorType::ValueError(ValueError::InvalidConversion {
@@ -93,6 +150,30 @@ pub(crate) fn sel(vm: &mut VM, selector: Option<RantValue>) -> RantStdResult {
   Ok(())
 }
 
+/// `[$sel-reset: selector (selector)]`
+///
+/// Resets a selector to its initial state, so that its next use starts a new sequence (e.g. reshuffles a deck).
+pub(crate) fn sel_reset(vm: &mut VM, selector: RantValue) -> RantStdResult {
+  match selector {
+    RantValue::Special(RantSpecial::Selector(selector)) => selector.borrow_mut().reset(),
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot reset '{}' value; expected a selector", other.type_name()),
+  }
+  Ok(())
+}
+
+/// `[$sel-progress: selector (selector)]`
+///
+/// Returns a float from 0 to 1 indicating how far through its current cycle the selector is, based on the elements it has selected so far.
+/// Returns an empty value for modes without a finite cycle (`random`, `one`, `no-double`, `weighted`, and custom modes).
+pub(crate) fn sel_progress(vm: &mut VM, selector: RantValue) -> RantStdResult {
+  let progress = match selector {
+    RantValue::Special(RantSpecial::Selector(selector)) => selector.borrow().progress(),
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot get progress of '{}' value; expected a selector", other.type_name()),
+  };
+  vm.cur_frame_mut().write_value(progress.map_or(RantValue::Empty, RantValue::Float));
+  Ok(())
+}
+
 pub(crate) fn push_attrs(vm: &mut VM, _: ()) -> RantStdResult {
   vm.resolver_mut().push_attrs();
   Ok(())
@@ -1,4 +1,4 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, mem};
 
 use super::*;
 use crate::{lang::PrintFlag};
@@ -107,6 +107,98 @@ pub(crate) fn filter(vm: &mut VM, (list, predicate): (RantListRef, RantFunctionR
   Ok(())
 }
 
+/// `[$find: list (list); predicate (function)]`
+///
+/// Returns the first element of `list` for which `predicate` returns `true`, or `~` if there is none.
+/// `predicate` is not called for any elements after the first match.
+pub(crate) fn find(vm: &mut VM, (list, predicate): (RantListRef, RantFunctionRef)) -> RantStdResult {
+  find_first(vm, list, predicate, true, "find", |vm, found| {
+    vm.cur_frame_mut().write_value(found.map_or(RantValue::Empty, |(_, element)| element));
+  })
+}
+
+/// `[$find-index: list (list); predicate (function)]`
+///
+/// Returns the index of the first element of `list` for which `predicate` returns `true`, or -1 if there is none.
+/// `predicate` is not called for any elements after the first match.
+pub(crate) fn find_index(vm: &mut VM, (list, predicate): (RantListRef, RantFunctionRef)) -> RantStdResult {
+  find_first(vm, list, predicate, true, "find-index", |vm, found| {
+    vm.cur_frame_mut().write_value(RantValue::Integer(found.map_or(-1, |(index, _)| index as i64)));
+  })
+}
+
+/// `[$any: list (list); predicate (function)]`
+///
+/// Returns `true` if `predicate` returns `true` for at least one element of `list`, stopping at the first such element.
+/// Returns `false` for an empty list.
+pub(crate) fn any(vm: &mut VM, (list, predicate): (RantListRef, RantFunctionRef)) -> RantStdResult {
+  find_first(vm, list, predicate, true, "any", |vm, found| {
+    vm.cur_frame_mut().write_value(RantValue::Boolean(found.is_some()));
+  })
+}
+
+/// `[$all: list (list); predicate (function)]`
+///
+/// Returns `true` if `predicate` returns `true` for every element of `list`, stopping at the first element for which it returns `false`.
+/// Returns `true` for an empty list.
+pub(crate) fn all(vm: &mut VM, (list, predicate): (RantListRef, RantFunctionRef)) -> RantStdResult {
+  find_first(vm, list, predicate, false, "all", |vm, found| {
+    vm.cur_frame_mut().write_value(RantValue::Boolean(found.is_none()));
+  })
+}
+
+/// Calls `predicate` on each element of `list` until it returns `stop_on`, then passes the index and element it stopped at
+/// (or `None` if it never did) to `on_finish`.
+fn find_first(vm: &mut VM, list: RantListRef, predicate: RantFunctionRef, stop_on: bool, func_name: &'static str, on_finish: fn(&mut VM, Option<(usize, RantValue)>)) -> RantStdResult {
+  fn _iterate_find(vm: &mut VM, src: RantListRef, index: usize, predicate: RantFunctionRef, stop_on: bool, func_name: &'static str, on_finish: fn(&mut VM, Option<(usize, RantValue)>)) -> RuntimeResult<()> {
+    let src_ref = src.borrow();
+
+    // Check predicate result from last iteration and stop once it matches stop_on
+    if index > 0 {
+      match vm.pop_val()? {
+        RantValue::Boolean(result) if result == stop_on => {
+          on_finish(vm, Some((index - 1, src_ref.get(index - 1).cloned().unwrap_or_default())));
+          return Ok(())
+        },
+        RantValue::Boolean(_) => {},
+        other => runtime_error!(RuntimeErrorType::TypeError, "{} callback expected to return 'bool' value, but returned '{}' instead", func_name, other.type_name())
+      }
+    }
+
+    // Check if search finished without a match
+    if index >= src_ref.len() {
+      on_finish(vm, None);
+      return Ok(())
+    }
+
+    let src_clone = Rc::clone(&src);
+    let predicate_arg = src_ref.get(index).cloned().unwrap_or_default();
+    let predicate_clone = Rc::clone(&predicate);
+
+    // Prepare next iteration
+    vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall(Box::new(move |vm| {
+      _iterate_find(vm, src_clone, index + 1, predicate, stop_on, func_name, on_finish)
+    })));
+
+    // Prepare predicate call for current iteration
+    vm.push_val(RantValue::Function(predicate_clone))?;
+    vm.push_val(predicate_arg)?;
+    vm.cur_frame_mut().push_intent_front(Intent::Call {
+      argc: 1,
+      flag: PrintFlag::None,
+      override_print: true,
+    });
+
+    Ok(())
+  }
+
+  vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall(Box::new(move |vm| {
+    _iterate_find(vm, list, 0, predicate, stop_on, func_name, on_finish)
+  })));
+
+  Ok(())
+}
+
 pub(crate) fn map(vm: &mut VM, (list, map_func): (RantListRef, RantFunctionRef)) -> RantStdResult {
   let list_ref = list.borrow();
   if list_ref.is_empty() {
@@ -158,6 +250,58 @@ pub(crate) fn map(vm: &mut VM, (list, map_func): (RantListRef, RantFunctionRef))
   Ok(())
 }
 
+/// `[$fold: list (list); init (any); reducer (function)]`
+///
+/// Calls `reducer` with `(accumulator; element)` for each element of `list`, using its return value as the accumulator for the next element,
+/// and returns the final accumulator. The first call receives `init` as the accumulator; if the list is empty, `init` is returned.
+pub(crate) fn fold(vm: &mut VM, (list, init, reducer): (RantListRef, RantValue, RantFunctionRef)) -> RantStdResult {
+  if list.borrow().is_empty() {
+    vm.cur_frame_mut().write_value(init);
+    return Ok(())
+  }
+
+  fn _iterate_fold(vm: &mut VM, src: RantListRef, index: usize, reducer: RantFunctionRef) -> RuntimeResult<()> {
+    let src_ref = src.borrow();
+
+    // The accumulator is on top of the stack, either from the initial push or from the last iteration
+    let acc = vm.pop_val()?;
+
+    // Check if folding finished
+    if index >= src_ref.len() {
+      vm.cur_frame_mut().write_value(acc);
+      return Ok(())
+    }
+
+    let src_clone = Rc::clone(&src);
+    let element = src_ref.get(index).cloned().unwrap_or_default();
+    let reducer_clone = Rc::clone(&reducer);
+
+    // Prepare next iteration
+    vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall(Box::new(move |vm| {
+      _iterate_fold(vm, src_clone, index + 1, reducer)
+    })));
+
+    // Prepare reducer call for current iteration
+    vm.push_val(RantValue::Function(reducer_clone))?;
+    vm.push_val(element)?;
+    vm.push_val(acc)?;
+    vm.cur_frame_mut().push_intent_front(Intent::Call {
+      argc: 2,
+      flag: PrintFlag::None,
+      override_print: true,
+    });
+
+    Ok(())
+  }
+
+  vm.push_val(init)?;
+  vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall(Box::new(move |vm| {
+    _iterate_fold(vm, list, 0, reducer)
+  })));
+
+  Ok(())
+}
+
 pub(crate) fn zip(vm: &mut VM, (list_a, list_b, zip_func): (RantListRef, RantListRef, RantFunctionRef)) -> RantStdResult {
   let (list_a_ref, list_b_ref) = (list_a.borrow(), list_b.borrow());
   let max_len = list_a_ref.len().max(list_b_ref.len());
@@ -205,6 +349,226 @@ pub(crate) fn zip(vm: &mut VM, (list_a, list_b, zip_func): (RantListRef, RantLis
   Ok(())
 }
 
+/// `[$zip-longest: list-a (list); list-b (list); fill? (any)]`
+///
+/// Returns a list of pairs (as two-element lists) of the elements at each index of `list-a` and `list-b`.
+/// Unlike `[zip]`, this continues to the end of the longer list; missing elements from the shorter list are replaced with `fill`.
+pub(crate) fn zip_longest(vm: &mut VM, (list_a, list_b, fill): (RantListRef, RantListRef, Option<RantValue>)) -> RantStdResult {
+  let (list_a, list_b) = (list_a.borrow(), list_b.borrow());
+  let fill = fill.unwrap_or(RantValue::Empty);
+  let max_len = list_a.len().max(list_b.len());
+
+  let pairs: RantList = (0..max_len)
+    .map(|i| {
+      let pair: RantList = vec![
+        list_a.get(i).cloned().unwrap_or_else(|| fill.clone()),
+        list_b.get(i).cloned().unwrap_or_else(|| fill.clone()),
+      ].into();
+      RantValue::List(Rc::new(RefCell::new(pair)))
+    })
+    .collect();
+
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(pairs))));
+  Ok(())
+}
+
+/// `[$interleave: list-a (list); list-b (list); extra-lists* (list)]`
+///
+/// Returns a new list that takes one element from each input list in turn until all of them are exhausted.
+/// Lists that run out early are skipped.
+pub(crate) fn interleave(vm: &mut VM, (list_a, list_b, extra_lists): (RantListRef, RantListRef, VarArgs<RantListRef>)) -> RantStdResult {
+  let list_refs: Vec<RantListRef> = vec![list_a, list_b].into_iter().chain(extra_lists.iter().cloned()).collect();
+  let lists: Vec<_> = list_refs.iter().map(|list| list.borrow()).collect();
+  let max_len = lists.iter().map(|list| list.len()).max().unwrap_or(0);
+
+  let mut interleaved = RantList::with_capacity(lists.iter().map(|list| list.len()).sum());
+  for i in 0..max_len {
+    for list in lists.iter() {
+      if let Some(val) = list.get(i) {
+        interleaved.push(val.clone());
+      }
+    }
+  }
+
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(interleaved))));
+  Ok(())
+}
+
+/// `[$flatten: list (list); depth? (int)]`
+///
+/// Returns a new list in which nested lists are replaced by their elements, up to `depth` levels deep.
+/// Omitting `depth` flattens the list completely; a depth of 1 only flattens lists directly inside `list`.
+/// Non-list elements are kept as they are.
+pub(crate) fn flatten(vm: &mut VM, (list, depth): (RantListRef, Option<i64>)) -> RantStdResult {
+  if let Some(depth) = depth {
+    if depth < 0 {
+      runtime_error!(RuntimeErrorType::ArgumentError, "flatten depth cannot be negative (got {})", depth);
+    }
+  }
+  let max_depth = depth.map(|depth| depth as usize);
+
+  // Use an explicit stack of (list, next index) pairs so that deeply nested lists can't overflow the native stack
+  let mut flat = RantList::new();
+  let mut stack: Vec<(RantListRef, usize)> = vec![(list, 0)];
+  while let Some((cur, index)) = stack.pop() {
+    let item = match cur.borrow().get(index) {
+      Some(item) => item.clone(),
+      None => continue,
+    };
+    // The number of lists above the current one is its nesting depth
+    let level = stack.len();
+    stack.push((cur, index + 1));
+    match item {
+      RantValue::List(sublist) if max_depth.map_or(true, |max_depth| level < max_depth) => {
+        if stack.iter().any(|(ancestor, _)| Rc::ptr_eq(ancestor, &sublist)) {
+          runtime_error!(RuntimeErrorType::ArgumentError, "cannot flatten a list that contains itself");
+        }
+        stack.push((sublist, 0));
+      },
+      item => flat.push(item),
+    }
+  }
+
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(flat))));
+  Ok(())
+}
+
+/// `[$unique: list (list)]`
+///
+/// Returns a new list containing the elements of `list` without duplicates, keeping the first occurrence of each.
+/// Elements are compared the same way as by `[eq]`.
+pub(crate) fn unique(vm: &mut VM, list: Vec<RantValue>) -> RantStdResult {
+  // RantValue can't be hashed, so each element is compared against all kept elements; this is O(n^2)
+  let mut unique = RantList::new();
+  for item in list {
+    if !unique.contains(&item) {
+      unique.push(item);
+    }
+  }
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(unique))));
+  Ok(())
+}
+
+/// `[$dedup: list (list)]`
+///
+/// Returns a new list in which runs of consecutive equal elements in `list` are reduced to a single element.
+/// Unlike `[unique]`, duplicates that aren't next to each other are kept.
+pub(crate) fn dedup(vm: &mut VM, mut list: Vec<RantValue>) -> RantStdResult {
+  list.dedup();
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(RantList::from(list)))));
+  Ok(())
+}
+
+/// `[$chunk: list (list); size (int)]`
+///
+/// Splits `list` into a list of consecutive sublists of length `size`. The last sublist is shorter if the elements don't divide evenly.
+pub(crate) fn chunk(vm: &mut VM, (list, size): (Vec<RantValue>, i64)) -> RantStdResult {
+  if size <= 0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "chunk size must be greater than zero (got {})", size);
+  }
+  let chunks = list
+    .chunks(size as usize)
+    .map(|chunk| RantValue::List(Rc::new(RefCell::new(RantList::from(chunk.to_vec())))))
+    .collect::<RantList>();
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(chunks))));
+  Ok(())
+}
+
+/// `[$windows: list (list); size (int)]`
+///
+/// Returns a list of all overlapping runs of `size` consecutive elements in `list`, in order.
+/// If `list` has fewer than `size` elements, the result is empty.
+pub(crate) fn windows(vm: &mut VM, (list, size): (Vec<RantValue>, i64)) -> RantStdResult {
+  if size <= 0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "window size must be greater than zero (got {})", size);
+  }
+  let windows = list
+    .windows(size as usize)
+    .map(|window| RantValue::List(Rc::new(RefCell::new(RantList::from(window.to_vec())))))
+    .collect::<RantList>();
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(windows))));
+  Ok(())
+}
+
+/// `[$gather: list (list); indices (list)]`
+///
+/// Returns a new list containing the elements of `list` at each of `indices`, in the order the indices are given.
+/// Negative indices count backwards from the end of the list.
+pub(crate) fn gather(vm: &mut VM, (list, indices): (RantListRef, Vec<i64>)) -> RantStdResult {
+  let list = list.borrow();
+  let len = list.len() as i64;
+
+  let mut gathered = RantList::with_capacity(indices.len());
+  for index in indices {
+    let uindex = if index < 0 { len + index } else { index };
+    if uindex < 0 || uindex >= len {
+      runtime_error!(RuntimeErrorType::IndexError(IndexError::OutOfRange), "index {} is out of range of list size", index);
+    }
+    gathered.push(list[uindex as usize].clone());
+  }
+
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(gathered))));
+  Ok(())
+}
+
+/// `[$with-index: list (list); index (int); value (any)]`
+///
+/// Returns a copy of `list` with the element at `index` replaced by `value`, leaving the original list unchanged.
+/// Negative indices count backwards from the end of the list.
+pub(crate) fn with_index(vm: &mut VM, (list, index, value): (RantListRef, i64, RantValue)) -> RantStdResult {
+  let mut list = list.borrow().clone();
+  let len = list.len() as i64;
+  let uindex = if index < 0 { len + index } else { index };
+  if uindex < 0 || uindex >= len {
+    runtime_error!(RuntimeErrorType::IndexError(IndexError::OutOfRange), "index {} is out of range of list size", index);
+  }
+  list[uindex as usize] = value;
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(list))));
+  Ok(())
+}
+
+/// `[$slice: value (list|string); start (int); end? (int)]`
+///
+/// Returns the elements of a list (as a new list) or the characters of a string from index `start` up to, but not including, index `end`.
+/// Negative indices count backwards from the end, and omitting `end` slices to the end.
+/// Out-of-range indices are clamped, and a range that is empty or reversed produces an empty list or string.
+pub(crate) fn slice(vm: &mut VM, (value, start, end): (RantValue, i64, Option<i64>)) -> RantStdResult {
+  let len = value.len();
+  let (start, end) = slice_bounds(len, start, end);
+  match value {
+    RantValue::List(list) => {
+      let sliced = list.borrow()[start..end].to_vec();
+      vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(RantList::from(sliced)))));
+    },
+    RantValue::String(s) => {
+      let sliced: String = s.chars().skip(start).take(end - start).collect();
+      vm.cur_frame_mut().write_value(RantValue::String(sliced));
+    },
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot slice '{}' value", other.type_name()),
+  }
+  Ok(())
+}
+
+/// Converts possibly negative slice indices into a clamped, non-reversed range of `0..len`.
+fn slice_bounds(len: usize, start: i64, end: Option<i64>) -> (usize, usize) {
+  let len = len as i64;
+  let clamp_index = |i: i64| if i < 0 { (len + i).max(0) } else { i.min(len) };
+  let start = clamp_index(start);
+  let end = end.map_or(len, clamp_index).max(start);
+  (start as usize, end as usize)
+}
+
+/// `[$with-key: map (map); key (string); value (any)]`
+///
+/// Returns a copy of `map` with `key` set to `value`, leaving the original map unchanged.
+/// The copy shares the original map's prototype.
+pub(crate) fn with_key(vm: &mut VM, (map, key, value): (RantMapRef, String, RantValue)) -> RantStdResult {
+  let mut map = map.borrow().clone();
+  map.raw_set(key.as_str(), value);
+  vm.cur_frame_mut().write_value(RantValue::Map(Rc::new(RefCell::new(map))));
+  Ok(())
+}
+
 pub(crate) fn pick(vm: &mut VM, list: RantValue) -> RantStdResult {
   let index = vm.rng().next_usize(list.len());
   let item = list.index_get(index as i64).into_runtime_result()?;
@@ -212,6 +576,105 @@ pub(crate) fn pick(vm: &mut VM, list: RantValue) -> RantStdResult {
   Ok(())
 }
 
+/// `[$weighted-sample: items (list); weights (list); n (int); replace? (bool)]`
+///
+/// Returns a list of `n` elements picked at random from `items`, where each element's chance of being picked is proportional to its weight in `weights`.
+/// If `replace` is true, the same element can be picked more than once; otherwise (the default), each picked element is removed from
+/// the pool and the remaining weights are renormalized.
+///
+/// `weights` must have one non-negative number for each item. Raises an error if there aren't enough items with non-zero weights to pick from.
+pub(crate) fn weighted_sample(vm: &mut VM, (items, weights, n, replace): (Vec<RantValue>, Vec<f64>, i64, Option<bool>)) -> RantStdResult {
+  if items.len() != weights.len() {
+    runtime_error!(RuntimeErrorType::ArgumentError, "weighted-sample expected {} weights, but got {}", items.len(), weights.len());
+  }
+  if let Some(weight) = weights.iter().find(|w| !w.is_finite() || **w < 0.0) {
+    runtime_error!(RuntimeErrorType::ArgumentError, "weights must be non-negative finite numbers (got {})", weight);
+  }
+  if n < 0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "sample size cannot be negative (got {})", n);
+  }
+
+  let n = n as usize;
+  let replace = replace.unwrap_or(false);
+  let available = weights.iter().filter(|w| **w > 0.0).count();
+  if n > 0 && (available == 0 || (!replace && n > available)) {
+    runtime_error!(RuntimeErrorType::ArgumentError, "cannot pick {} items from {} items with non-zero weights", n, available);
+  }
+
+  let mut weights = weights;
+  let mut sample = RantList::with_capacity(n);
+  let rng = vm.rng();
+  for _ in 0..n {
+    // Recompute the total on each draw so removed weights can't leave floating-point drift behind
+    let total_weight: f64 = weights.iter().sum();
+    if total_weight <= 0.0 {
+      runtime_error!(RuntimeErrorType::ArgumentError, "no items with non-zero weights are left to pick from");
+    }
+
+    // Walk the cumulative weights; rounding can leave `r` just past the end, in which case the last eligible item is picked
+    let mut r = rng.next_f64(0.0, total_weight);
+    let mut index = None;
+    for (i, w) in weights.iter().enumerate() {
+      if *w <= 0.0 {
+        continue
+      }
+      index = Some(i);
+      if r < *w {
+        break
+      }
+      r -= w;
+    }
+
+    let index = match index {
+      Some(index) => index,
+      None => runtime_error!(RuntimeErrorType::ArgumentError, "no items with non-zero weights are left to pick from"),
+    };
+    sample.push(items[index].clone());
+    if !replace {
+      weights[index] = 0.0;
+    }
+  }
+
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(sample))));
+  Ok(())
+}
+
+/// `[$pick-deck: list (list)]`
+///
+/// Returns a function that picks a random element from a copy of `list` each time it's called,
+/// without picking any element twice until all of them have been picked. The elements are then reshuffled.
+/// This works like the `deck` selector mode, but for use outside of blocks.
+pub(crate) fn pick_deck(vm: &mut VM, list: Vec<RantValue>) -> RantStdResult {
+  if list.is_empty() {
+    runtime_error!(RuntimeErrorType::ArgumentError, "cannot pick from an empty list");
+  }
+
+  // Indices of the elements left in the current deck, drawn from the end
+  let deck: RefCell<Vec<usize>> = RefCell::new(vec![]);
+
+  let body = RantFunctionInterface::Foreign(Rc::new(move |vm: &mut VM, _: Vec<RantValue>| {
+    let mut deck = deck.borrow_mut();
+    if deck.is_empty() {
+      deck.extend(0..list.len());
+      vm.rng().shuffle(&mut deck);
+    }
+    let index = deck.pop().unwrap();
+    vm.cur_frame_mut().write_value(list[index].clone());
+    Ok(())
+  }));
+
+  let picker = RantFunction {
+    params: Rc::new(vec![]),
+    min_arg_count: 0,
+    vararg_start_index: 0,
+    captured_vars: vec![],
+    body,
+  };
+
+  vm.cur_frame_mut().write_value(RantValue::Function(Rc::new(picker)));
+  Ok(())
+}
+
 pub(crate) fn join(vm: &mut VM, (sep, list): (RantValue, Vec<RantValue>)) -> RantStdResult {
   let mut is_first = true;
   let frame = vm.cur_frame_mut();
@@ -260,6 +723,98 @@ pub(crate) fn sum(vm: &mut VM, list: RantListRef) -> RantStdResult {
   Ok(())
 }
 
+/// `[$mean: list (list)]`
+///
+/// Returns the arithmetic mean of the numbers in `list` as a float. Raises an error if the list is empty or contains non-numeric values.
+pub(crate) fn mean(vm: &mut VM, list: RantListRef) -> RantStdResult {
+  let values = numeric_list_values(&list.borrow(), "mean")?;
+  let mean = values.iter().sum::<f64>() / values.len() as f64;
+  vm.cur_frame_mut().write_value(RantValue::Float(mean));
+  Ok(())
+}
+
+/// `[$median: list (list)]`
+///
+/// Returns the middle value of the numbers in `list` when sorted. For an even number of values, returns the mean of the two middle values as a float.
+/// Raises an error if the list is empty or contains non-numeric values.
+pub(crate) fn median(vm: &mut VM, list: RantListRef) -> RantStdResult {
+  let list = list.borrow();
+  numeric_list_values(&list, "median")?;
+  let mut sorted: Vec<&RantValue> = list.iter().collect();
+  sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+  let mid = sorted.len() / 2;
+  let median = if sorted.len() % 2 == 1 {
+    sorted[mid].clone()
+  } else {
+    let as_float = |val: &RantValue| match val.clone().into_rant_float() {
+      RantValue::Float(n) => n,
+      _ => unreachable!(),
+    };
+    RantValue::Float((as_float(sorted[mid - 1]) + as_float(sorted[mid])) / 2.0)
+  };
+  vm.cur_frame_mut().write_value(median);
+  Ok(())
+}
+
+/// Gets the values of a non-empty list of numbers as floats, raising an error if the list is empty or has non-numeric elements.
+fn numeric_list_values(list: &RantList, func_name: &str) -> RuntimeResult<Vec<f64>> {
+  if list.is_empty() {
+    runtime_error!(RuntimeErrorType::ArgumentError, "cannot get {} of an empty list", func_name);
+  }
+  let mut values = Vec::with_capacity(list.len());
+  for val in list.iter() {
+    match val {
+      RantValue::Integer(n) => values.push(*n as f64),
+      RantValue::Float(n) => values.push(*n),
+      other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot get {} of list containing '{}' value", func_name, other.type_name()),
+    }
+  }
+  Ok(values)
+}
+
+/// `[$sum-values: map (map); skip-non-numeric? (bool)]`
+///
+/// Returns the sum of the numeric values in `map`.
+/// Non-numeric values raise an error, unless `skip-non-numeric` is true, in which case they are ignored.
+pub(crate) fn sum_values(vm: &mut VM, (map, skip_non_numeric): (RantMapRef, Option<bool>)) -> RantStdResult {
+  let (sum, _) = sum_numeric_map_values(&map.borrow(), skip_non_numeric.unwrap_or(false))?;
+  vm.cur_frame_mut().write_value(sum);
+  Ok(())
+}
+
+/// `[$mean-values: map (map); skip-non-numeric? (bool)]`
+///
+/// Returns the arithmetic mean of the numeric values in `map` as a float, or nothing if there are no values to average.
+/// Non-numeric values raise an error, unless `skip-non-numeric` is true, in which case they are ignored.
+pub(crate) fn mean_values(vm: &mut VM, (map, skip_non_numeric): (RantMapRef, Option<bool>)) -> RantStdResult {
+  let (sum, count) = sum_numeric_map_values(&map.borrow(), skip_non_numeric.unwrap_or(false))?;
+  let mean = match sum {
+    _ if count == 0 => RantValue::Empty,
+    RantValue::Integer(n) => RantValue::Float(n as f64 / count as f64),
+    RantValue::Float(n) => RantValue::Float(n / count as f64),
+    _ => unreachable!(),
+  };
+  vm.cur_frame_mut().write_value(mean);
+  Ok(())
+}
+
+/// Sums the numeric values of a map and returns the sum along with the number of values added.
+fn sum_numeric_map_values(map: &RantMap, skip_non_numeric: bool) -> RuntimeResult<(RantValue, usize)> {
+  let mut sum = RantValue::Integer(0);
+  let mut count = 0;
+  for val in map.raw_values().iter() {
+    match val {
+      RantValue::Integer(_) | RantValue::Float(_) => {
+        sum = sum + val.clone();
+        count += 1;
+      },
+      _ if skip_non_numeric => {},
+      other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot sum non-numeric map value of type '{}'", other.type_name()),
+    }
+  }
+  Ok((sum, count))
+}
+
 pub(crate) fn min(vm: &mut VM, list: RantListRef) -> RantStdResult {
   let list = list.borrow();
   if list.is_empty() {
@@ -300,22 +855,45 @@ pub(crate) fn max(vm: &mut VM, list: RantListRef) -> RantStdResult {
   Ok(())
 }
 
-pub(crate) fn shuffled(vm: &mut VM, list: RantListRef) -> RantStdResult {
-  let mut list = list.borrow().clone();
-  if list.is_empty() {
-    return Ok(());
+/// `[$min-max: list (list)]`
+///
+/// Returns a list containing the smallest and largest values in `list`, in that order, found in a single pass.
+/// Raises an error if the list is empty.
+pub(crate) fn min_max(vm: &mut VM, list: RantListRef) -> RantStdResult {
+  let list = list.borrow();
+  let mut iter = list.iter();
+  let first = match iter.next() {
+    Some(first) => first,
+    None => runtime_error!(RuntimeErrorType::ArgumentError, "cannot get min-max of an empty list"),
+  };
+  let (mut min, mut max) = (first, first);
+
+  for val in iter {
+    if val < min {
+      min = val;
+    } else if val > max {
+      max = val;
+    }
   }
 
-  let n = list.len();
-  let rng = vm.rng();
+  let pair = RantList::from(vec![min.clone(), max.clone()]);
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(pair))));
 
-  for i in 0..n {
-    let swap_index = rng.next_usize(n);
-    list.swap(i, swap_index);
-  }
+  Ok(())
+}
 
+/// `[$shuffled: list (list); key? (int|string)]`
+///
+/// Returns a shuffled copy of `list`. The original list is left untouched.
+///
+/// If `key` is specified, the permutation is derived from the seed and `key` alone (see `[shuffle]`).
+pub(crate) fn shuffled(vm: &mut VM, (list, key): (RantListRef, Option<RantValue>)) -> RantStdResult {
+  let mut list = list.borrow().clone();
+  match keyed_rng(vm, key)? {
+    Some(rng) => rng.shuffle(&mut list),
+    None => vm.rng().shuffle(&mut list),
+  }
   vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(list))));
-  
   Ok(())
 }
 
@@ -330,11 +908,171 @@ pub(crate) fn clear(vm: &mut VM, collection: RantValue) -> RantStdResult {
   Ok(())
 }
 
+/// `[$clone: collection (list|map)]`
+///
+/// Returns a shallow copy of a list or map. The new collection is separate from the original,
+/// but any collections nested inside it are shared with the original.
+/// Use `[copy]` for a deep copy.
+pub(crate) fn clone(vm: &mut VM, collection: RantValue) -> RantStdResult {
+  match collection {
+    RantValue::List(_) | RantValue::Map(_) => vm.cur_frame_mut().write_value(collection.shallow_copy()),
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot clone value of type '{}'; expected list or map", other.type_name()),
+  }
+  Ok(())
+}
+
 pub(crate) fn keys(vm: &mut VM, map: RantMapRef) -> RantStdResult {
   vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(map.borrow().raw_keys()))));
   Ok(())
 }
 
+/// `[$entries: map (map)]`
+///
+/// Returns a list of `(key; value)` pairs for the entries of `map`, sorted by key.
+/// Keys inherited from the map's prototype are not included.
+pub(crate) fn entries(vm: &mut VM, map: RantMapRef) -> RantStdResult {
+  let map = map.borrow();
+  let mut pairs: Vec<(&str, &RantValue)> = map.raw_pairs().collect();
+  pairs.sort_unstable_by_key(|(key, _)| *key);
+  let entries = pairs
+    .into_iter()
+    .map(|(key, val)| RantValue::List(Rc::new(RefCell::new(RantList::from(vec![RantValue::String(key.to_owned()), val.clone()])))))
+    .collect::<RantList>();
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(entries))));
+  Ok(())
+}
+
+/// `[$deep-merge: map-a (map); map-b (map); list-mode? (string)]`
+///
+/// Returns a new map containing the entries of `map-a` recursively merged with the entries of `map-b`.
+/// Where both maps have a map under the same key, the two are merged recursively; otherwise the value from `map-b` wins.
+///
+/// `list-mode` controls what happens when both maps have a list under the same key:
+/// `replace` (the default) keeps the list from `map-b`, while `concat` appends it to the list from `map-a`.
+pub(crate) fn deep_merge(vm: &mut VM, (map_a, map_b, list_mode): (RantMapRef, RantMapRef, Option<String>)) -> RantStdResult {
+  let concat_lists = match list_mode.as_deref() {
+    None | Some("replace") => false,
+    Some("concat") => true,
+    Some(other) => runtime_error!(RuntimeErrorType::ArgumentError, "invalid list merge mode: '{}'", other),
+  };
+  if has_cycle(&RantValue::Map(Rc::clone(&map_a))) || has_cycle(&RantValue::Map(Rc::clone(&map_b))) {
+    runtime_error!(RuntimeErrorType::ArgumentError, "cannot merge maps that contain themselves");
+  }
+  let merged = deep_merge_maps(&map_a.borrow(), &map_b.borrow(), concat_lists);
+  vm.cur_frame_mut().write_value(RantValue::Map(Rc::new(RefCell::new(merged))));
+  Ok(())
+}
+
+fn deep_merge_maps(map_a: &RantMap, map_b: &RantMap, concat_lists: bool) -> RantMap {
+  let mut merged = map_a.deep_copy();
+  for (key, val_b) in map_b.raw_pairs() {
+    let merged_val = match (merged.raw_get(key), val_b) {
+      (Some(RantValue::Map(sub_a)), RantValue::Map(sub_b)) => {
+        RantValue::Map(Rc::new(RefCell::new(deep_merge_maps(&sub_a.borrow(), &sub_b.borrow(), concat_lists))))
+      },
+      (Some(RantValue::List(list_a)), RantValue::List(list_b)) if concat_lists => {
+        let list = list_a.borrow().iter().chain(list_b.borrow().iter()).map(RantValue::deep_copy).collect::<RantList>();
+        RantValue::List(Rc::new(RefCell::new(list)))
+      },
+      (_, val_b) => val_b.deep_copy(),
+    };
+    merged.raw_set(key, merged_val);
+  }
+  merged
+}
+
+/// `[$flatten-map: map (map); sep? (string)]`
+///
+/// Returns a single-level copy of `map` in which nested maps and lists are replaced by their entries,
+/// keyed by the path to each entry joined with `sep` (defaults to `.`). List elements use their index as the path component.
+/// Empty maps and lists are kept as values. Only own entries are included; prototypes are ignored.
+pub(crate) fn flatten_map(vm: &mut VM, (map, sep): (RantMapRef, Option<String>)) -> RantStdResult {
+  let sep = sep.unwrap_or_else(|| ".".to_owned());
+  let mut flat = RantMap::new();
+  let mut guard = CycleGuard::new();
+  guard.enter(Rc::as_ptr(&map) as *const ());
+  for (key, val) in map.borrow().raw_pairs() {
+    flatten_entry(key.to_owned(), val, sep.as_str(), &mut flat, &mut guard)?;
+  }
+  vm.cur_frame_mut().write_value(RantValue::Map(Rc::new(RefCell::new(flat))));
+  Ok(())
+}
+
+fn flatten_entry(path: String, val: &RantValue, sep: &str, flat: &mut RantMap, guard: &mut CycleGuard) -> Result<(), RuntimeError> {
+  let children: Vec<(String, RantValue)> = match val {
+    RantValue::Map(map) if !map.borrow().is_empty() => {
+      map.borrow().raw_pairs().map(|(key, val)| (key.to_owned(), val.clone())).collect()
+    },
+    RantValue::List(list) if !list.borrow().is_empty() => {
+      list.borrow().iter().enumerate().map(|(i, val)| (i.to_string(), val.clone())).collect()
+    },
+    _ => {
+      flat.raw_set(path.as_str(), val.clone());
+      return Ok(())
+    }
+  };
+
+  if !guard.enter(CycleGuard::identity_of(val).unwrap()) {
+    runtime_error!(RuntimeErrorType::ArgumentError, "cannot flatten map: value at '{}' contains one of its parent collections", path);
+  }
+
+  for (key, child) in children {
+    flatten_entry(format!("{}{}{}", path, sep, key), &child, sep, flat, guard)?;
+  }
+  guard.exit();
+  Ok(())
+}
+
+/// `[$unflatten-map: map (map); sep? (string)]`
+///
+/// The inverse of `[flatten-map]`: returns a nested copy of `map` in which each key is split on `sep` (defaults to `.`)
+/// and its value is stored under the resulting path of nested maps.
+/// Raises an error if a key is a prefix of another key, since the prefix would need to be both a value and a map.
+pub(crate) fn unflatten_map(vm: &mut VM, (map, sep): (RantMapRef, Option<String>)) -> RantStdResult {
+  let sep = sep.unwrap_or_else(|| ".".to_owned());
+  if sep.is_empty() {
+    runtime_error!(RuntimeErrorType::ArgumentError, "unflatten-map separator cannot be empty");
+  }
+
+  let root = Rc::new(RefCell::new(RantMap::new()));
+  for (key, val) in map.borrow().raw_pairs() {
+    let mut path: Vec<&str> = key.split(sep.as_str()).collect();
+    let last = path.pop().unwrap();
+    let mut cur = Rc::clone(&root);
+    for component in path {
+      let next = match cur.borrow_mut().raw_get(component) {
+        Some(RantValue::Map(next)) => Rc::clone(next),
+        Some(_) => runtime_error!(RuntimeErrorType::ArgumentError, "cannot unflatten map: key '{}' conflicts with a value at '{}'", key, component),
+        None => Rc::new(RefCell::new(RantMap::new())),
+      };
+      cur.borrow_mut().raw_set(component, RantValue::Map(Rc::clone(&next)));
+      cur = next;
+    }
+    if cur.borrow().raw_has_key(last) {
+      runtime_error!(RuntimeErrorType::ArgumentError, "cannot unflatten map: key '{}' conflicts with another key", key);
+    }
+    cur.borrow_mut().raw_set(last, val.clone());
+  }
+  vm.cur_frame_mut().write_value(RantValue::Map(root));
+  Ok(())
+}
+
+/// `[$keys-count: map (map)]`
+///
+/// Returns the number of keys in `map`. Only the map's own entries are counted; keys inherited from its prototype are ignored.
+pub(crate) fn keys_count(vm: &mut VM, map: RantMapRef) -> RantStdResult {
+  vm.cur_frame_mut().write_value(RantValue::Integer(map.borrow().raw_len() as i64));
+  Ok(())
+}
+
+/// `[$values-count: map (map)]`
+///
+/// Returns the number of values in `map`. Like `[keys-count]`, this only counts the map's own entries, so the two always agree.
+pub(crate) fn values_count(vm: &mut VM, map: RantMapRef) -> RantStdResult {
+  vm.cur_frame_mut().write_value(RantValue::Integer(map.borrow().raw_len() as i64));
+  Ok(())
+}
+
 pub(crate) fn assoc(vm: &mut VM, (keys, values): (RantListRef, RantListRef)) -> RantStdResult {
   let keys = keys.borrow();
   let values = values.borrow();
@@ -377,6 +1115,18 @@ pub(crate) fn list_pop(vm: &mut VM, list: RantListRef) -> RantStdResult {
   Ok(())
 }
 
+/// `[$pad-list: list (list); length (int); fill (any)]`
+///
+/// Appends copies of `fill` to `list` in place until it contains `length` elements.
+/// Lists that are already at least `length` elements long are left unchanged.
+pub(crate) fn pad_list(vm: &mut VM, (list, length, fill): (RantListRef, usize, RantValue)) -> RantStdResult {
+  let mut list = list.borrow_mut();
+  while list.len() < length {
+    list.push(fill.shallow_copy());
+  }
+  Ok(())
+}
+
 pub(crate) fn sift(vm: &mut VM, (list, size): (RantListRef, usize)) -> RantStdResult {
   let mut list = list.borrow_mut();
   if list.len() <= size {
@@ -524,6 +1274,16 @@ pub(crate) fn take(vm: &mut VM, (collection, pos): (RantValue, RantValue)) -> Ra
   Ok(())
 }
 
+/// `[$take-key: map (map); key (string)]`
+///
+/// Removes `key` from `map` and returns its previous value, or `~` if the key wasn't present.
+/// Only the map's own entries are affected; keys inherited from its prototype are ignored.
+pub(crate) fn take_key(vm: &mut VM, (map, key): (RantMapRef, String)) -> RantStdResult {
+  let val = map.borrow_mut().raw_take(key.as_str()).unwrap_or(RantValue::Empty);
+  vm.cur_frame_mut().write_value(val);
+  Ok(())
+}
+
 pub(crate) fn sort(vm: &mut VM, list: RantListRef) -> RantStdResult {
   let mut list = list.borrow_mut();
   list.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
@@ -537,20 +1297,165 @@ pub(crate) fn sorted(vm: &mut VM, list: RantListRef) -> RantStdResult {
   Ok(())
 }
 
-pub(crate) fn shuffle(vm: &mut VM, list: RantListRef) -> RantStdResult {
-  let mut list = list.borrow_mut();
-  if list.is_empty() {
-    return Ok(())
+/// `[$reverse: val (list|string)]`
+///
+/// Returns a reversed copy of a list, or prints a string with its characters in reverse order. The original list is not modified.
+pub(crate) fn reverse(vm: &mut VM, val: RantValue) -> RantStdResult {
+  let reversed = match val {
+    RantValue::List(list) => RantValue::List(Rc::new(RefCell::new(list.borrow().iter().rev().cloned().collect()))),
+    RantValue::String(s) => RantValue::String(s.chars().rev().collect()),
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot reverse '{}' value; expected a list or string", other.type_name()),
+  };
+  vm.cur_frame_mut().write_value(reversed);
+  Ok(())
+}
+
+/// `[$sort-indices: list (list)]`
+///
+/// Returns a list of the indices that would sort `list` in ascending order.
+/// The sort is stable, so equal elements keep their original relative order.
+pub(crate) fn sort_indices(vm: &mut VM, list: RantListRef) -> RantStdResult {
+  let list = list.borrow();
+  let indices = sorted_indices_by_key(&list);
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(indices))));
+  Ok(())
+}
+
+/// `[$sort-indices-by: list (list); key-func (function)]`
+///
+/// Returns a list of the indices that would sort `list` in ascending order of the keys returned by `key-func` for each element.
+/// The sort is stable, so elements with equal keys keep their original relative order.
+pub(crate) fn sort_indices_by(vm: &mut VM, (list, key_func): (RantListRef, RantFunctionRef)) -> RantStdResult {
+  collect_keys_then(vm, list, key_func, |vm, _, keys| {
+    let indices = sorted_indices_by_key(&keys);
+    vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(indices))));
+    Ok(())
+  })
+}
+
+/// Calls `key_func` on each element of `src` in order, then passes `src` and the collected keys to `finish`.
+fn collect_keys_then(vm: &mut VM, src: RantListRef, key_func: RantFunctionRef, finish: fn(&mut VM, RantListRef, RantList) -> RuntimeResult<()>) -> RuntimeResult<()> {
+  fn _iterate_keys(vm: &mut VM, src: RantListRef, mut keys: RantList, index: usize, key_func: RantFunctionRef, finish: fn(&mut VM, RantListRef, RantList) -> RuntimeResult<()>) -> RuntimeResult<()> {
+    let src_ref = src.borrow();
+
+    // Add key from last iteration
+    if index > 0 {
+      keys.push(vm.pop_val()?);
+    }
+
+    // Finish once all keys are collected
+    if index >= src_ref.len() {
+      drop(src_ref);
+      return finish(vm, src, keys)
+    }
+
+    let src_clone = Rc::clone(&src);
+    let key_func_arg = src_ref.get(index).cloned().unwrap_or_default();
+    let key_func_clone = Rc::clone(&key_func);
+
+    // Prepare next iteration
+    vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall(Box::new(move |vm| {
+      _iterate_keys(vm, src_clone, keys, index + 1, key_func, finish)
+    })));
+
+    // Prepare key function call for current iteration
+    vm.push_val(RantValue::Function(key_func_clone))?;
+    vm.push_val(key_func_arg)?;
+    vm.cur_frame_mut().push_intent_front(Intent::Call {
+      argc: 1,
+      flag: PrintFlag::None,
+      override_print: true,
+    });
+
+    Ok(())
   }
 
-  let n = list.len();
-  let rng = vm.rng();
-  for i in 0..n {
-    list.swap(i, rng.next_usize(n));
+  vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall(Box::new(move |vm| {
+    _iterate_keys(vm, src, RantList::new(), 0, key_func, finish)
+  })));
+
+  Ok(())
+}
+
+/// Returns the indices of `keys` in stable ascending order of their values.
+fn sorted_indices_by_key(keys: &[RantValue]) -> RantList {
+  let mut indices: Vec<usize> = (0..keys.len()).collect();
+  indices.sort_by(|&a, &b| keys[a].partial_cmp(&keys[b]).unwrap_or(Ordering::Equal));
+  indices.into_iter().map(|i| RantValue::Integer(i as i64)).collect()
+}
+
+/// `[$group-runs: list (list); key-func? (function)]`
+///
+/// Returns a list of sublists, each containing a run of consecutive equal elements from `list`.
+/// If `key-func` is specified, elements are grouped by the value it returns for each of them instead.
+pub(crate) fn group_runs(vm: &mut VM, (list, key_func): (RantListRef, Option<RantFunctionRef>)) -> RantStdResult {
+  match key_func {
+    Some(key_func) => collect_keys_then(vm, list, key_func, |vm, src, keys| {
+      let runs = group_runs_by_key(&src.borrow(), &keys);
+      vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(runs))));
+      Ok(())
+    }),
+    None => {
+      let runs = {
+        let list = list.borrow();
+        group_runs_by_key(&list, &list)
+      };
+      vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(runs))));
+      Ok(())
+    }
+  }
+}
+
+/// Groups consecutive elements of `values` with equal `keys` into sublists.
+fn group_runs_by_key(values: &[RantValue], keys: &[RantValue]) -> RantList {
+  let mut runs = RantList::new();
+  let mut cur_run = RantList::new();
+  let mut prev_key: Option<&RantValue> = None;
+
+  for (value, key) in values.iter().zip(keys.iter()) {
+    if prev_key.map_or(false, |prev_key| prev_key != key) {
+      runs.push(RantValue::List(Rc::new(RefCell::new(mem::take(&mut cur_run)))));
+    }
+    cur_run.push(value.clone());
+    prev_key = Some(key);
+  }
+
+  if !cur_run.is_empty() {
+    runs.push(RantValue::List(Rc::new(RefCell::new(cur_run))));
+  }
+
+  runs
+}
+
+/// `[$shuffle: list (list); key? (int|string)]`
+///
+/// Shuffles the elements of `list` in place.
+///
+/// The permutation is produced by a Fisher-Yates shuffle driven by the active RNG,
+/// so the same seed and RNG state always yield the same ordering.
+/// This permutation is stable: a given seed produces the same ordering across releases, and changing it is considered a breaking change.
+///
+/// If `key` is specified, the shuffle instead uses an RNG forked from the active seed and `key` (as with `[fork]`),
+/// so the permutation doesn't depend on how many random values were drawn before it, and the active RNG is left unchanged.
+pub(crate) fn shuffle(vm: &mut VM, (list, key): (RantListRef, Option<RantValue>)) -> RantStdResult {
+  let mut list = list.borrow_mut();
+  match keyed_rng(vm, key)? {
+    Some(rng) => rng.shuffle(&mut list),
+    None => vm.rng().shuffle(&mut list),
   }
   Ok(())
 }
 
+/// Creates an RNG forked from the active seed and a shuffle key, if one is specified.
+fn keyed_rng(vm: &VM, key: Option<RantValue>) -> Result<Option<RantRng>, RuntimeError> {
+  Ok(match key {
+    Some(RantValue::Integer(i)) => Some(vm.rng().fork_i64(i)),
+    Some(RantValue::String(s)) => Some(vm.rng().fork_str(&s)),
+    Some(other) => runtime_error!(RuntimeErrorType::ArgumentError, "shuffle key cannot be of type '{}'", other.type_name()),
+    None => None,
+  })
+}
+
 pub(crate) fn has(vm: &mut VM, (value, key): (RantValue, RantValue)) -> RantStdResult {
   let result = match (value, key) {
     (RantValue::Map(map), RantValue::String(key)) => {
@@ -0,0 +1,83 @@
+use super::*;
+
+/// `[$rgb: r (int); g (int); b (int)]`
+///
+/// Prints a color as a `#rrggbb` hex string. Each channel is clamped to the range 0-255.
+pub(crate) fn rgb(vm: &mut VM, (r, g, b): (i64, i64, i64)) -> RantStdResult {
+  let hex = format_hex_color(util::clamp(r, 0, 255) as u8, util::clamp(g, 0, 255) as u8, util::clamp(b, 0, 255) as u8);
+  vm.cur_frame_mut().write_frag(hex.as_str());
+  Ok(())
+}
+
+/// `[$hex-to-rgb: hex (string)]`
+///
+/// Returns the channels of a hex color string as an `(r; g; b)` list.
+/// Accepts `rrggbb` and shorthand `rgb` forms, with or without a leading `#`.
+pub(crate) fn hex_to_rgb(vm: &mut VM, hex: String) -> RantStdResult {
+  let (r, g, b) = match parse_hex_color(hex.as_str()) {
+    Some(channels) => channels,
+    None => runtime_error!(RuntimeErrorType::ArgumentError, "'{}' is not a valid hex color", hex),
+  };
+  let list = RantList::from(vec![RantValue::Integer(r as i64), RantValue::Integer(g as i64), RantValue::Integer(b as i64)]);
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(list))));
+  Ok(())
+}
+
+/// `[$lerp-color: from (string); to (string); t (number)]`
+///
+/// Prints the color a fraction `t` of the way from `from` to `to` as a `#rrggbb` hex string, interpolating each channel linearly.
+/// `t` is clamped to the range 0-1. Both colors accept the same forms as `[hex-to-rgb]`.
+pub(crate) fn lerp_color(vm: &mut VM, (from, to, t): (String, String, f64)) -> RantStdResult {
+  let ((r1, g1, b1), (r2, g2, b2)) = match (parse_hex_color(from.as_str()), parse_hex_color(to.as_str())) {
+    (Some(from), Some(to)) => (from, to),
+    (None, _) => runtime_error!(RuntimeErrorType::ArgumentError, "'{}' is not a valid hex color", from),
+    (_, None) => runtime_error!(RuntimeErrorType::ArgumentError, "'{}' is not a valid hex color", to),
+  };
+  if t.is_nan() {
+    runtime_error!(RuntimeErrorType::ArgumentError, "interpolation factor cannot be NaN");
+  }
+  let t = util::clamp(t, 0.0, 1.0);
+  let lerp = |a: u8, b: u8| util::clamp((a as f64 + (b as f64 - a as f64) * t).round(), 0.0, 255.0) as u8;
+  let hex = format_hex_color(lerp(r1, r2), lerp(g1, g2), lerp(b1, b2));
+  vm.cur_frame_mut().write_frag(hex.as_str());
+  Ok(())
+}
+
+/// Parses a hex color string into its red, green, and blue channels.
+pub(crate) fn parse_hex_color(hex: &str) -> Option<(u8, u8, u8)> {
+  let digits = hex.strip_prefix('#').unwrap_or(hex);
+  if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+    return None
+  }
+
+  let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+  match digits.len() {
+    6 => Some((channel(&digits[0..2])?, channel(&digits[2..4])?, channel(&digits[4..6])?)),
+    // Shorthand: each digit is doubled
+    3 => Some((channel(&digits[0..1])? * 17, channel(&digits[1..2])? * 17, channel(&digits[2..3])? * 17)),
+    _ => None
+  }
+}
+
+/// Formats color channels as a `#rrggbb` hex string.
+pub(crate) fn format_hex_color(r: u8, g: u8, b: u8) -> String {
+  format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Converts a color from HSL to RGB. Hue is in degrees; saturation and lightness are in the range 0-1.
+pub(crate) fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+  let h = h.rem_euclid(360.0) / 60.0;
+  let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+  let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+  let (r, g, b) = match h as u8 {
+    0 => (chroma, x, 0.0),
+    1 => (x, chroma, 0.0),
+    2 => (0.0, chroma, x),
+    3 => (0.0, x, chroma),
+    4 => (x, 0.0, chroma),
+    _ => (chroma, 0.0, x),
+  };
+  let m = l - chroma / 2.0;
+  let channel = |c: f64| util::clamp(((c + m) * 255.0).round(), 0.0, 255.0) as u8;
+  (channel(r), channel(g), channel(b))
+}
\ No newline at end of file
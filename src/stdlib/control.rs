@@ -5,6 +5,15 @@ pub(crate) fn if_(vm: &mut VM, condition: bool) -> RantStdResult {
   Ok(())
 }
 
+/// `[$cond: condition (bool)]`
+///
+/// Works like `[if]`, but also returns `condition` so that the decision can be stored or reused.
+pub(crate) fn cond(vm: &mut VM, condition: bool) -> RantStdResult {
+  vm.resolver_mut().attrs_mut().make_if(condition);
+  vm.cur_frame_mut().write_value(RantValue::Boolean(condition));
+  Ok(())
+}
+
 pub(crate) fn else_if(vm: &mut VM, condition: bool) -> RantStdResult {
   vm.resolver_mut().attrs_mut().make_else_if(condition);
   Ok(())
@@ -28,4 +37,18 @@ pub(crate) fn continue_(vm: &mut VM, val: Option<RantValue>) -> RantStdResult {
 pub(crate) fn return_(vm: &mut VM, val: Option<RantValue>) -> RantStdResult {
   vm.func_return(val)?;
   Ok(())
+}
+
+/// `[$guard: condition (bool)]`
+///
+/// Returns from the current function with no value if `condition` is false; otherwise, does nothing.
+/// Raises an error if called outside of a function.
+pub(crate) fn guard(vm: &mut VM, condition: bool) -> RantStdResult {
+  if !vm.is_in_function() {
+    runtime_error!(RuntimeErrorType::ControlFlowError, "guard can only be used inside a function");
+  }
+  if !condition {
+    vm.func_return(None)?;
+  }
+  Ok(())
 }
\ No newline at end of file
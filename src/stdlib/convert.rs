@@ -13,4 +13,28 @@ pub(crate) fn to_float(vm: &mut VM, value: RantValue) -> RantStdResult {
 pub(crate) fn to_string(vm: &mut VM, value: RantValue) -> RantStdResult {
   vm.cur_frame_mut().write_value(value.into_rant_string());
   Ok(())
+}
+
+/// `[$num-str: n (number); max-decimals? (int)]`
+///
+/// Prints a number in a prose-friendly form: floats without a fractional part are printed without a decimal point,
+/// and other floats are rounded to at most `max-decimals` decimal places (if specified) with trailing zeros removed.
+pub(crate) fn num_str(vm: &mut VM, (n, max_decimals): (RantValue, Option<usize>)) -> RantStdResult {
+  let s = match n {
+    RantValue::Integer(n) => n.to_string(),
+    RantValue::Float(n) => {
+      let s = match max_decimals {
+        Some(max_decimals) => format!("{:.*}", max_decimals, n),
+        None => n.to_string(),
+      };
+      if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_owned()
+      } else {
+        s
+      }
+    },
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "num-str expected a number, but got '{}'", other.type_name()),
+  };
+  vm.cur_frame_mut().write_frag(s.as_str());
+  Ok(())
 }
\ No newline at end of file
@@ -30,4 +30,57 @@ pub(crate) fn whitespace_fmt(vm: &mut VM, (mode, custom): (Option<String>, Optio
     }
   }
   Ok(())
+}
+
+/// `[$nl]`
+///
+/// Prints a line break (`\n`), regardless of the current whitespace normalization mode.
+pub(crate) fn nl(vm: &mut VM, _: ()) -> RantStdResult {
+  vm.cur_frame_mut().write_frag("\n");
+  Ok(())
+}
+
+/// `[$tab]`
+///
+/// Prints a tab character, regardless of the current whitespace normalization mode.
+pub(crate) fn tab(vm: &mut VM, _: ()) -> RantStdResult {
+  vm.cur_frame_mut().write_frag("\t");
+  Ok(())
+}
+
+/// `[$space: count? (int)]`
+///
+/// Prints `count` space characters (default: 1), regardless of the current whitespace normalization mode.
+pub(crate) fn space(vm: &mut VM, count: Option<usize>) -> RantStdResult {
+  vm.cur_frame_mut().write_frag(" ".repeat(count.unwrap_or(1)).as_str());
+  Ok(())
+}
+
+/// `[$collapse-blanks: text (string); max? (int)]`
+///
+/// Prints `text` with each run of consecutive blank lines shortened to at most `max` lines (default: 1).
+/// Lines containing only whitespace count as blank.
+pub(crate) fn collapse_blanks(vm: &mut VM, (text, max): (String, Option<usize>)) -> RantStdResult {
+  let max = max.unwrap_or(1);
+  let mut blank_run = 0;
+  let lines: Vec<&str> = text.split('\n').filter(|line| {
+    if line.trim().is_empty() {
+      blank_run += 1;
+      blank_run <= max
+    } else {
+      blank_run = 0;
+      true
+    }
+  }).collect();
+  vm.cur_frame_mut().write_frag(lines.join("\n").as_str());
+  Ok(())
+}
+
+/// `[$percent: x (number); decimals? (int)]`
+///
+/// Prints a fraction as a percentage with the specified number of decimal places (default: 0), so that 0.25 becomes `25%`.
+/// Values outside of [0, 1] are scaled the same way rather than clamped.
+pub(crate) fn percent(vm: &mut VM, (x, decimals): (f64, Option<usize>)) -> RantStdResult {
+  vm.cur_frame_mut().write_frag(format!("{:.*}%", decimals.unwrap_or(0), x * 100.0).as_str());
+  Ok(())
 }
\ No newline at end of file
@@ -35,9 +35,13 @@ pub(crate) fn call(vm: &mut VM, (func, args): (RantFunctionRef, Option<Vec<RantV
 
 /// `[$copy: val (any)]`
 ///
-/// Returns a copy of a value.
+/// Returns a deep copy of a value. Nested lists and maps are copied as well.
+/// Use `[clone]` for a shallow copy.
 pub(crate) fn copy(vm: &mut VM, val: RantValue) -> RantStdResult {
-  vm.cur_frame_mut().write_value(val.shallow_copy());
+  if has_cycle(&val) {
+    runtime_error!(RuntimeErrorType::ArgumentError, "cannot deep-copy a collection that contains itself");
+  }
+  vm.cur_frame_mut().write_value(val.deep_copy());
   Ok(())
 }
 
@@ -79,6 +83,137 @@ pub(crate) fn unfork(vm: &mut VM, _: ()) -> RantStdResult {
   Ok(())
 }
 
+/// `[$env: key (string)]`
+///
+/// Returns a copy of the value stored under `key` in the host-provided environment map, or `~` if there is no such key.
+/// Collections are deep-copied so that the environment can't be modified by the program.
+pub(crate) fn env(vm: &mut VM, key: String) -> RantStdResult {
+  let val = vm.context().options.environment.raw_get(key.as_str()).map(RantValue::deep_copy).unwrap_or(RantValue::Empty);
+  vm.cur_frame_mut().write_value(val);
+  Ok(())
+}
+
+/// `[$rng-save]`
+///
+/// Returns an opaque snapshot of the current RNG state, which can be passed to `[rng-restore]` to replay the random values that follow.
+pub(crate) fn rng_save(vm: &mut VM, _: ()) -> RantStdResult {
+  let state = vm.rng().save_state();
+  vm.cur_frame_mut().write_value(RantValue::Special(RantSpecial::RngState(Rc::new(state))));
+  Ok(())
+}
+
+/// `[$rng-restore: state (special)]`
+///
+/// Restores the current RNG to a state previously returned by `[rng-save]`.
+pub(crate) fn rng_restore(vm: &mut VM, state: RantValue) -> RantStdResult {
+  match state {
+    RantValue::Special(RantSpecial::RngState(state)) => vm.rng().restore_state(&state),
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "expected an RNG state, but got '{}'", other.type_name()),
+  }
+  Ok(())
+}
+
+/// `[$memoize: func (function)]`
+///
+/// Returns a new function that caches the results of `func`, keyed by the types and contents of the arguments it receives.
+/// Repeated calls with equal arguments return the cached result without calling `func` again.
+/// Lists and maps are compared element by element, so two different lists with the same elements share a cache entry.
+///
+/// This assumes that `func` is pure. Calls with arguments that can't be compared by content (functions, blocks, special values,
+/// or cyclic collections) always call `func` and are not cached.
+pub(crate) fn memoize(vm: &mut VM, func: RantFunctionRef) -> RantStdResult {
+  let cache = Rc::new(RefCell::new(RantMap::new()));
+  let inner = Rc::clone(&func);
+
+  let body = RantFunctionInterface::Foreign(Rc::new(move |vm: &mut VM, args: Vec<RantValue>| {
+    let mut key = String::new();
+    let is_cacheable = args.iter().all(|arg| !has_cycle(arg) && write_memo_key(arg, &mut key));
+
+    // Return cached result if available
+    if is_cacheable {
+      if let Some(cached) = cache.borrow().raw_get(key.as_str()).cloned() {
+        vm.cur_frame_mut().write_value(cached);
+        return Ok(())
+      }
+    }
+
+    // Store the result once the wrapped function returns
+    let cache = Rc::clone(&cache);
+    vm.cur_frame_mut().push_intent_front(Intent::RuntimeCall(Box::new(move |vm| {
+      let result = vm.pop_val()?;
+      if is_cacheable {
+        cache.borrow_mut().raw_set(key.as_str(), result.clone());
+      }
+      vm.cur_frame_mut().write_value(result);
+      Ok(())
+    })));
+
+    // Call the wrapped function
+    let argc = args.len();
+    vm.push_val(RantValue::Function(Rc::clone(&inner)))?;
+    for arg in args.into_iter().rev() {
+      vm.push_val(arg)?;
+    }
+    vm.cur_frame_mut().push_intent_front(Intent::Call { argc, flag: PrintFlag::None, override_print: true });
+    Ok(())
+  }));
+
+  let memoized = RantFunction {
+    params: Rc::clone(&func.params),
+    min_arg_count: func.min_arg_count,
+    vararg_start_index: func.vararg_start_index,
+    captured_vars: vec![],
+    body,
+  };
+
+  vm.cur_frame_mut().write_value(RantValue::Function(Rc::new(memoized)));
+  Ok(())
+}
+
+/// Appends an unambiguous encoding of `val` to a `[memoize]` cache key: the type name, followed by the length-prefixed contents.
+/// Lists and maps are encoded element by element, with map entries sorted by key.
+/// Returns `false` if the value can't be encoded by content; the value must not be cyclic.
+fn write_memo_key(val: &RantValue, key: &mut String) -> bool {
+  use std::fmt::Write;
+
+  // Length prefixes keep strings containing separators from colliding with other encodings
+  fn write_text(key: &mut String, text: &str) {
+    let _ = write!(key, "{}:{};", text.len(), text);
+  }
+
+  key.push_str(val.type_name());
+  match val {
+    RantValue::String(s) => write_text(key, s.as_str()),
+    RantValue::Integer(n) => write_text(key, n.to_string().as_str()),
+    RantValue::Float(n) => write_text(key, n.to_string().as_str()),
+    RantValue::Boolean(b) => write_text(key, if *b { "true" } else { "false" }),
+    RantValue::Empty => key.push(';'),
+    RantValue::List(list) => {
+      let list = list.borrow();
+      let _ = write!(key, "({};", list.len());
+      if !list.iter().all(|item| write_memo_key(item, key)) {
+        return false
+      }
+      key.push(')');
+    },
+    RantValue::Map(map) => {
+      let map = map.borrow();
+      let mut pairs: Vec<(&str, &RantValue)> = map.raw_pairs().collect();
+      pairs.sort_by_key(|(map_key, _)| *map_key);
+      let _ = write!(key, "({};", pairs.len());
+      for (map_key, map_val) in pairs {
+        write_text(key, map_key);
+        if !write_memo_key(map_val, key) {
+          return false
+        }
+      }
+      key.push(')');
+    },
+    RantValue::Function(_) | RantValue::Block(_) | RantValue::Special(_) => return false,
+  }
+  true
+}
+
 /// `[$nop]`
 ///
 /// Does absolutely nothing. Intended for use as a default/placeholder callback.
@@ -98,11 +233,73 @@ pub(crate) fn seed(vm: &mut VM, _: ()) -> RantStdResult {
   Ok(())
 }
 
+/// `[$stack-depth]`
+///
+/// Returns the current depth of the call stack, not counting the frame of `[stack-depth]` itself.
+pub(crate) fn stack_depth(vm: &mut VM, _: ()) -> RantStdResult {
+  let depth = vm.call_stack_depth().saturating_sub(1);
+  vm.cur_frame_mut().write_value(RantValue::Integer(depth as i64));
+  Ok(())
+}
+
+/// `[$here]`
+///
+/// Prints the source location of the call as `origin:line:col`, where `origin` is the path or name of the program.
+/// Prints nothing if the program was compiled without debug symbols, since locations aren't tracked then.
+pub(crate) fn here(vm: &mut VM, _: ()) -> RantStdResult {
+  // Native calls inherit the location of their caller
+  let frame = vm.cur_frame();
+  let (line, col) = frame.debug_pos();
+  if (line, col) == (0, 0) {
+    return Ok(())
+  }
+  let location = format!("{}:{}:{}", frame.origin_name(), line, col);
+  vm.cur_frame_mut().write_frag(location.as_str());
+  Ok(())
+}
+
+/// `[$len: val (any)]`
+///
+/// Prints the length of a value:
+/// * strings: the number of characters
+/// * lists: the number of elements
+/// * maps: the number of keys (not counting keys inherited from the prototype)
+/// * blocks: the number of elements
+/// * empty: 0
+/// * anything else: 1
 pub(crate) fn len(vm: &mut VM, val: RantValue) -> RantStdResult {
   vm.cur_frame_mut().write_value(RantValue::Integer(val.len() as i64));
   Ok(())
 }
 
+/// `[$cap: value (string|list); max (int)]`
+///
+/// Returns `value` truncated to at most `max` characters (for strings) or elements (for lists).
+/// Values already within `max` are returned unchanged; truncated lists are returned as new lists.
+pub(crate) fn cap(vm: &mut VM, (value, max): (RantValue, i64)) -> RantStdResult {
+  if max < 0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "cap max must be non-negative");
+  }
+  let max = max as usize;
+  let capped = match value {
+    RantValue::String(s) => match s.char_indices().nth(max) {
+      Some((end, _)) => RantValue::String(s[..end].to_owned()),
+      None => RantValue::String(s),
+    },
+    RantValue::List(list) => {
+      if list.borrow().len() <= max {
+        RantValue::List(list)
+      } else {
+        let capped: RantList = list.borrow().iter().take(max).cloned().collect();
+        RantValue::List(Rc::new(RefCell::new(capped)))
+      }
+    },
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot cap value of type '{}'", other.type_name()),
+  };
+  vm.cur_frame_mut().write_value(capped);
+  Ok(())
+}
+
 pub(crate) fn error(vm: &mut VM, msg: Option<String>) -> RantStdResult {
   const DEFAULT_ERROR_MESSAGE: &str = "user error";
   Err(RuntimeError {
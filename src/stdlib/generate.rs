@@ -12,6 +12,63 @@ pub(crate) fn randf(vm: &mut VM, (a, b): (f64, f64)) -> RantStdResult {
   Ok(())
 }
 
+/// `[$reseed: seed (int)]`
+///
+/// Reinitializes the current RNG with the specified seed, so that the random values that follow are the same regardless of any values generated before.
+/// The current seed can be read with `[seed]`.
+pub(crate) fn reseed(vm: &mut VM, seed: i64) -> RantStdResult {
+  vm.rng().reseed(seed as u64);
+  Ok(())
+}
+
+/// `[$randf-normal: mean (float); stddev (float)]`
+///
+/// Returns a random float from a normal distribution with the specified mean and standard deviation.
+pub(crate) fn randf_normal(vm: &mut VM, (mean, stddev): (f64, f64)) -> RantStdResult {
+  check_stddev(stddev)?;
+  let n = next_gaussian(vm.rng(), mean, stddev);
+  vm.cur_frame_mut().write_value(RantValue::Float(n));
+  Ok(())
+}
+
+/// `[$randf-normal-clamped: mean (float); stddev (float); min (float); max (float)]`
+///
+/// Returns a random float from a normal distribution with the specified mean and standard deviation, limited to the range `[min, max]`.
+/// Values outside the range are discarded and resampled; if no value in range is found after a fixed number of attempts, the last value is clamped instead.
+pub(crate) fn randf_normal_clamped(vm: &mut VM, (mean, stddev, min, max): (f64, f64, f64, f64)) -> RantStdResult {
+  const MAX_ATTEMPTS: usize = 100;
+  check_stddev(stddev)?;
+  if min.is_nan() || max.is_nan() || min > max {
+    runtime_error!(RuntimeErrorType::ArgumentError, "randf-normal-clamped requires min <= max, but got {} and {}", min, max);
+  }
+  let rng = vm.rng();
+  let mut n = next_gaussian(rng, mean, stddev);
+  for _ in 1..MAX_ATTEMPTS {
+    if n >= min && n <= max {
+      break
+    }
+    n = next_gaussian(rng, mean, stddev);
+  }
+  vm.cur_frame_mut().write_value(RantValue::Float(util::clamp(n, min, max)));
+  Ok(())
+}
+
+fn check_stddev(stddev: f64) -> RuntimeResult<()> {
+  if !stddev.is_finite() || stddev < 0.0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "standard deviation must be a non-negative finite number, but got {}", stddev);
+  }
+  Ok(())
+}
+
+/// Samples a normal distribution using the Box-Muller transform.
+fn next_gaussian(rng: &RantRng, mean: f64, stddev: f64) -> f64 {
+  // Flip the first sample into (0, 1] so that its logarithm is finite
+  let u1 = 1.0 - rng.next_normal_f64();
+  let u2 = rng.next_normal_f64();
+  let z = (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+  mean + z * stddev
+}
+
 pub(crate) fn rand_list(vm: &mut VM, (a, b, n): (i64, i64, usize)) -> RantStdResult {
   let mut list = RantList::new();
   let rng = vm.rng();
@@ -22,6 +79,28 @@ pub(crate) fn rand_list(vm: &mut VM, (a, b, n): (i64, i64, usize)) -> RantStdRes
   Ok(())
 }
 
+/// `[$range: start (int); end (int); step? (int)]`
+///
+/// Returns a list of integers from `start` (inclusive) to `end` (exclusive), counting by `step` (default: 1).
+/// A negative `step` counts down. If `step` points away from `end`, the list is empty. Raises an error if `step` is zero.
+pub(crate) fn range(vm: &mut VM, (start, end, step): (i64, i64, Option<i64>)) -> RantStdResult {
+  let step = step.unwrap_or(1);
+  if step == 0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "range step cannot be zero");
+  }
+  let mut list = RantList::new();
+  let mut cur = start;
+  while (step > 0 && cur < end) || (step < 0 && cur > end) {
+    list.push(RantValue::Integer(cur));
+    cur = match cur.checked_add(step) {
+      Some(next) => next,
+      None => break,
+    };
+  }
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(list))));
+  Ok(())
+}
+
 pub(crate) fn randf_list(vm: &mut VM, (a, b, n): (f64, f64, usize)) -> RantStdResult {
   let mut list = RantList::new();
   let rng = vm.rng();
@@ -45,6 +124,53 @@ pub(crate) fn alpha(vm: &mut VM, count: Option<usize>) -> RantStdResult {
   Ok(())
 }
 
+/// `[$alpha-weighted: count? (int); lang? (string)]`
+///
+/// Prints `count` (default: 1) random lowercase letters, weighted by their frequency in a language.
+/// Supported languages are `en` (the default), `de`, `es`, and `fr`; accented letters are not included.
+pub(crate) fn alpha_weighted(vm: &mut VM, (count, lang): (Option<usize>, Option<String>)) -> RantStdResult {
+  // Letter frequencies (in percent) for a-z
+  const EN: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406, 
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+  ];
+  const DE: [f64; 26] = [
+    6.516, 1.886, 2.732, 5.076, 16.396, 1.656, 3.009, 4.577, 6.550, 0.268, 1.417, 3.437, 2.534, 
+    9.776, 2.594, 0.670, 0.018, 7.003, 7.270, 6.154, 4.166, 0.846, 1.921, 0.034, 0.039, 1.134,
+  ];
+  const ES: [f64; 26] = [
+    11.525, 2.215, 4.019, 5.010, 12.181, 0.692, 1.768, 0.703, 6.247, 0.493, 0.011, 4.967, 3.157, 
+    6.712, 8.683, 2.510, 0.877, 6.871, 7.977, 4.632, 2.927, 1.138, 0.017, 0.215, 1.008, 0.467,
+  ];
+  const FR: [f64; 26] = [
+    7.636, 0.901, 3.260, 3.669, 14.715, 1.066, 0.866, 0.737, 7.529, 0.613, 0.074, 5.456, 2.968, 
+    7.095, 5.796, 2.521, 1.362, 6.693, 7.948, 7.244, 6.311, 1.838, 0.049, 0.427, 0.128, 0.326,
+  ];
+
+  let weights = match lang.as_deref().unwrap_or("en") {
+    "en" => &EN,
+    "de" => &DE,
+    "es" => &ES,
+    "fr" => &FR,
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "no letter frequencies available for language '{}'", other),
+  };
+  let total_weight: f64 = weights.iter().sum();
+
+  let count = count.unwrap_or(1);
+  let mut s = String::with_capacity(count);
+  let rng = vm.rng();
+  for _ in 0..count {
+    let mut n = rng.next_f64(0.0, total_weight);
+    let index = weights.iter().position(|w| {
+      n -= w;
+      n < 0.0
+    }).unwrap_or(weights.len() - 1);
+    s.push((b'a' + index as u8) as char);
+  }
+  vm.cur_frame_mut().write_frag(s.as_str());
+  Ok(())
+}
+
 pub(crate) fn digh(vm: &mut VM, count: Option<usize>) -> RantStdResult {
   const CHARS: &[u8] = b"0123456789abcdef";
   let count = count.unwrap_or(1);
@@ -161,4 +287,165 @@ pub(crate) fn maybe(vm: &mut VM, p: Option<f64>) -> RantStdResult {
   let b = vm.rng().next_bool(p.unwrap_or(0.5));
   vm.cur_frame_mut().write_value(RantValue::Boolean(b));
   Ok(())
+}
+
+/// `[$uuid]`
+///
+/// Prints a random version 4 UUID in its hyphenated lowercase form (e.g. `0b8e3f52-7c1d-4a9e-b6f0-2d5c8a1e9f47`).
+/// The UUID is generated from the current RNG, so it is reproducible for a fixed seed.
+pub(crate) fn uuid(vm: &mut VM, _: ()) -> RantStdResult {
+  let rng = vm.rng();
+  let mut bytes = [0u8; 16];
+  bytes[..8].copy_from_slice(&rng.next_u64().to_be_bytes());
+  bytes[8..].copy_from_slice(&rng.next_u64().to_be_bytes());
+  // Set version (4) and variant (RFC 4122) bits
+  bytes[6] = (bytes[6] & 0x0f) | 0x40;
+  bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+  let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+  let uuid = format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..]);
+  vm.cur_frame_mut().write_frag(uuid.as_str());
+  Ok(())
+}
+
+/// `[$random-color: format? (string); saturation? (list); lightness? (list)]`
+///
+/// Returns a random color with a random hue. `format` can be `hex` (the default) for a `#rrggbb` string or `rgb` for an `(r; g; b)` list.
+///
+/// `saturation` and `lightness` are optional `(min; max)` bounds in the range 0-1; if omitted, the full range is used.
+pub(crate) fn random_color(vm: &mut VM, (format, saturation, lightness): (Option<String>, Option<Vec<f64>>, Option<Vec<f64>>)) -> RantStdResult {
+  fn bounds(name: &str, bounds: Option<Vec<f64>>) -> Result<(f64, f64), RuntimeError> {
+    match bounds.as_deref() {
+      None => Ok((0.0, 1.0)),
+      Some(&[min, max]) => Ok((util::clamp(min, 0.0, 1.0), util::clamp(max, 0.0, 1.0))),
+      Some(_) => runtime_error!(RuntimeErrorType::ArgumentError, "{} bounds must be a list of two numbers", name),
+    }
+  }
+
+  let (sat_min, sat_max) = bounds("saturation", saturation)?;
+  let (light_min, light_max) = bounds("lightness", lightness)?;
+
+  let rng = vm.rng();
+  let hue = rng.next_f64(0.0, 360.0);
+  let sat = rng.next_f64(sat_min, sat_max);
+  let light = rng.next_f64(light_min, light_max);
+  let (r, g, b) = hsl_to_rgb(hue, sat, light);
+
+  match format.as_deref() {
+    None | Some("hex") => vm.cur_frame_mut().write_frag(format_hex_color(r, g, b).as_str()),
+    Some("rgb") => {
+      let list = RantList::from(vec![RantValue::Integer(r as i64), RantValue::Integer(g as i64), RantValue::Integer(b as i64)]);
+      vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(list))));
+    },
+    Some(other) => runtime_error!(RuntimeErrorType::ArgumentError, "unknown color format '{}'; expected 'hex' or 'rgb'", other),
+  }
+  Ok(())
+}
+
+/// `[$lorem: word-count (int); paragraphs? (int)]`
+///
+/// Prints `word-count` words of lorem ipsum placeholder text, grouped into sentences of roughly 4 to 12 words.
+/// If `paragraphs` is specified, the words are divided as evenly as possible into that many paragraphs, separated by blank lines.
+pub(crate) fn lorem(vm: &mut VM, (word_count, paragraphs): (usize, Option<usize>)) -> RantStdResult {
+  const WORDS: &[&str] = &[
+    "lorem", "ipsum", "dolor", "sit", "amet", "consectetur", "adipiscing", "elit", "sed", "do", 
+    "eiusmod", "tempor", "incididunt", "ut", "labore", "et", "dolore", "magna", "aliqua", "enim", 
+    "ad", "minim", "veniam", "quis", "nostrud", "exercitation", "ullamco", "laboris", "nisi", "aliquip", 
+    "ex", "ea", "commodo", "consequat", "duis", "aute", "irure", "in", "reprehenderit", "voluptate", 
+    "velit", "esse", "cillum", "fugiat", "nulla", "pariatur", "excepteur", "sint", "occaecat", "cupidatat", 
+    "non", "proident", "sunt", "culpa", "qui", "officia", "deserunt", "mollit", "anim", "id", "est", "laborum",
+  ];
+  const MIN_SENTENCE_WORDS: usize = 4;
+  const MAX_SENTENCE_WORDS: usize = 12;
+
+  let paragraph_count = util::clamp(paragraphs.unwrap_or(1), 1, word_count.max(1));
+  let rng = vm.rng();
+  let mut text_paragraphs = Vec::with_capacity(paragraph_count);
+
+  for i in 0..paragraph_count {
+    // Spread any remainder over the first paragraphs
+    let mut words_left = word_count / paragraph_count + if i < word_count % paragraph_count { 1 } else { 0 };
+    let mut sentences = vec![];
+
+    while words_left > 0 {
+      let mut sentence_len = (MIN_SENTENCE_WORDS + rng.next_usize(MAX_SENTENCE_WORDS - MIN_SENTENCE_WORDS + 1)).min(words_left);
+      // Don't leave a remainder too short to be its own sentence
+      if words_left - sentence_len < MIN_SENTENCE_WORDS {
+        sentence_len = words_left;
+      }
+      words_left -= sentence_len;
+      let words: Vec<&str> = (0..sentence_len).map(|_| WORDS[rng.next_usize(WORDS.len())]).collect();
+      let mut sentence = words.join(" ");
+      // Capitalize the first letter and end with a period
+      sentence.replace_range(..1, &sentence[..1].to_uppercase());
+      sentence.push('.');
+      sentences.push(sentence);
+    }
+
+    text_paragraphs.push(sentences.join(" "));
+  }
+
+  vm.cur_frame_mut().write_frag(text_paragraphs.join("\n\n").as_str());
+  Ok(())
+}
+
+/// `[$gen-name: syllables? (int); phonemes? (map)]`
+///
+/// Prints a random pronounceable name made of `syllables` syllables (default: 2 or 3).
+/// Each syllable is a vowel, optionally surrounded by consonants; consecutive syllables never put two vowels next to each other.
+///
+/// A custom phoneme set can be passed as a map with `consonants` and `vowels` lists.
+pub(crate) fn gen_name(vm: &mut VM, (syllables, phonemes): (Option<usize>, Option<RantMapRef>)) -> RantStdResult {
+  const CONSONANTS: &[&str] = &[
+    "b", "d", "f", "g", "h", "k", "l", "m", "n", "p", "r", "s", "t", "v", "z", 
+    "th", "sh", "br", "dr", "kr", "st",
+  ];
+  const VOWELS: &[&str] = &["a", "e", "i", "o", "u", "ae", "ai", "ei", "ou"];
+  const ONSET_CHANCE: f64 = 0.8;
+  const CODA_CHANCE: f64 = 0.3;
+
+  fn phoneme_list(phonemes: &RantMap, key: &str) -> Result<Vec<String>, RuntimeError> {
+    match phonemes.raw_get(key) {
+      Some(RantValue::List(list)) if !list.borrow().is_empty() => Ok(list.borrow().iter().map(|p| p.to_string()).collect()),
+      _ => runtime_error!(RuntimeErrorType::ArgumentError, "phoneme set must have a non-empty '{}' list", key),
+    }
+  }
+
+  let (consonants, vowels) = match phonemes {
+    Some(phonemes) => {
+      let phonemes = phonemes.borrow();
+      (phoneme_list(&phonemes, "consonants")?, phoneme_list(&phonemes, "vowels")?)
+    },
+    None => (
+      CONSONANTS.iter().map(|p| p.to_string()).collect(), 
+      VOWELS.iter().map(|p| p.to_string()).collect()
+    ),
+  };
+
+  let rng = vm.rng();
+  let syllables = syllables.unwrap_or_else(|| 2 + rng.next_usize(2));
+  let mut name = String::new();
+  let mut ends_with_vowel = false;
+
+  for _ in 0..syllables {
+    if ends_with_vowel || rng.next_bool(ONSET_CHANCE) {
+      name.push_str(&consonants[rng.next_usize(consonants.len())]);
+    }
+    name.push_str(&vowels[rng.next_usize(vowels.len())]);
+    ends_with_vowel = true;
+    if rng.next_bool(CODA_CHANCE) {
+      name.push_str(&consonants[rng.next_usize(consonants.len())]);
+      ends_with_vowel = false;
+    }
+  }
+
+  // Capitalize the first letter
+  let mut chars = name.chars();
+  let name = match chars.next() {
+    Some(first) => first.to_uppercase().chain(chars).collect(),
+    None => name,
+  };
+
+  vm.cur_frame_mut().write_frag(name.as_str());
+  Ok(())
 }
\ No newline at end of file
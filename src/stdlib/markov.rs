@@ -0,0 +1,77 @@
+use super::*;
+use fnv::FnvHashMap;
+
+/// Word-level Markov model built from a corpus. Words are borrowed from the corpus.
+struct MarkovModel<'a> {
+  /// Maps each sequence of `order` words to the words that follow it in the corpus (including repeats, so more common transitions are more likely).
+  transitions: FnvHashMap<Vec<&'a str>, Vec<&'a str>>,
+  /// Word sequences that begin the corpus or a sentence in it.
+  starts: Vec<Vec<&'a str>>,
+}
+
+impl<'a> MarkovModel<'a> {
+  fn build(corpus: &'a str, order: usize) -> Option<Self> {
+    let words: Vec<&str> = corpus.split_whitespace().collect();
+    if words.len() <= order {
+      return None
+    }
+
+    let mut transitions: FnvHashMap<Vec<&str>, Vec<&str>> = Default::default();
+    let mut starts = vec![];
+    for i in 0..=(words.len() - order) {
+      let state = words[i..i + order].to_vec();
+      if i == 0 || words[i - 1].ends_with(['.', '!', '?']) {
+        starts.push(state.clone());
+      }
+      if let Some(next) = words.get(i + order) {
+        transitions.entry(state).or_default().push(next);
+      }
+    }
+
+    Some(Self {
+      transitions,
+      starts,
+    })
+  }
+}
+
+/// `[$markov: corpus (string); length (int); order? (int)]`
+///
+/// Prints `length` words generated by a word-level Markov chain of the specified order (default: 1) built from `corpus`.
+/// Generation begins at the start of a sentence in the corpus, and starts over at another sentence whenever it reaches a dead end.
+pub(crate) fn markov(vm: &mut VM, (corpus, length, order): (String, usize, Option<usize>)) -> RantStdResult {
+  let order = order.unwrap_or(1);
+  if order == 0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "markov chain order must be at least 1");
+  }
+
+  let model = match MarkovModel::build(corpus.as_str(), order) {
+    Some(model) => model,
+    None => runtime_error!(RuntimeErrorType::ArgumentError, "corpus needs more than {} words to build a markov chain of order {}", order, order),
+  };
+
+  let rng = vm.rng();
+  let mut output: Vec<&str> = Vec::with_capacity(length);
+  let mut state: Vec<&str> = vec![];
+
+  while output.len() < length {
+    match model.transitions.get(state.as_slice()) {
+      // Continue the chain
+      Some(next_words) => {
+        let next = next_words[rng.next_usize(next_words.len())];
+        output.push(next);
+        state.remove(0);
+        state.push(next);
+      },
+      // Start a new chain
+      None => {
+        let start = &model.starts[rng.next_usize(model.starts.len())];
+        state.clone_from(start);
+        output.extend(state.iter().take(length - output.len()));
+      }
+    }
+  }
+
+  vm.cur_frame_mut().write_frag(output.join(" ").as_str());
+  Ok(())
+}
\ No newline at end of file
@@ -13,6 +13,74 @@ pub(crate) fn clamp(vm: &mut VM, (value, a, b): (RantValue, RantValue, RantValue
   Ok(())
 }
 
+/// `[$abs-diff: a (integer|float); b (integer|float)]`
+///
+/// Gets the absolute difference between two numbers.
+pub(crate) fn abs_diff(vm: &mut VM, (a, b): (RantValue, RantValue)) -> RantStdResult {
+  let diff = match (a, b) {
+    (RantValue::Integer(a), RantValue::Integer(b)) => RantValue::Integer((a as i128 - b as i128).abs().min(i64::MAX as i128) as i64),
+    (RantValue::Integer(a), RantValue::Float(b)) => RantValue::Float((a as f64 - b).abs()),
+    (RantValue::Float(a), RantValue::Integer(b)) => RantValue::Float((a - b as f64).abs()),
+    (RantValue::Float(a), RantValue::Float(b)) => RantValue::Float((a - b).abs()),
+    (a, b) => runtime_error!(RuntimeErrorType::ArgumentError, "cannot get absolute difference of '{}' and '{}' values", a.type_name(), b.type_name()),
+  };
+  vm.cur_frame_mut().write_value(diff);
+  Ok(())
+}
+
+/// `[$distance: list-a (list); list-b (list)]`
+///
+/// Gets the Euclidean distance between two equal-length lists of numbers.
+pub(crate) fn distance(vm: &mut VM, (list_a, list_b): (Vec<f64>, Vec<f64>)) -> RantStdResult {
+  if list_a.len() != list_b.len() {
+    runtime_error!(RuntimeErrorType::ArgumentError, "distance requires lists of equal length, but got lengths {} and {}", list_a.len(), list_b.len());
+  }
+  let dist = list_a.iter().zip(list_b.iter()).map(|(a, b)| (a - b) * (a - b)).sum::<f64>().sqrt();
+  vm.cur_frame_mut().write_value(RantValue::Float(dist));
+  Ok(())
+}
+
+/// `[$clamp01: x (integer|float)]`
+///
+/// Clamps a number to the range [0, 1].
+pub(crate) fn clamp01(vm: &mut VM, x: RantValue) -> RantStdResult {
+  let clamped = match x {
+    RantValue::Integer(n) => RantValue::Integer(util::clamp(n, 0, 1)),
+    RantValue::Float(n) => RantValue::Float(util::clamp(n, 0.0, 1.0)),
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot use clamp01 function on '{}' value", other.type_name()),
+  };
+  vm.cur_frame_mut().write_value(clamped);
+  Ok(())
+}
+
+/// `[$remap: x (number); in-min (number); in-max (number); out-min (number); out-max (number); clamp? (bool)]`
+///
+/// Linearly rescales a number from one range to another, optionally clamping the result to the output range.
+pub(crate) fn remap(vm: &mut VM, (x, in_min, in_max, out_min, out_max, clamp): (f64, f64, f64, f64, f64, Option<bool>)) -> RantStdResult {
+  if in_min == in_max {
+    runtime_error!(RuntimeErrorType::ArgumentError, "cannot remap from a zero-width input range");
+  }
+  let mut result = out_min + (x - in_min) * (out_max - out_min) / (in_max - in_min);
+  if clamp.unwrap_or(false) {
+    result = util::clamp(result, out_min, out_max);
+  }
+  vm.cur_frame_mut().write_value(RantValue::Float(result));
+  Ok(())
+}
+
+/// `[$snap: x (number); step (number); origin? (number)]`
+///
+/// Rounds a number to the nearest multiple of `step`, offset by `origin` (default: 0).
+pub(crate) fn snap(vm: &mut VM, (x, step, origin): (f64, f64, Option<f64>)) -> RantStdResult {
+  if step == 0.0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "cannot snap to a step of zero");
+  }
+  let origin = origin.unwrap_or(0.0);
+  let snapped = ((x - origin) / step).round() * step + origin;
+  vm.cur_frame_mut().write_value(RantValue::Float(snapped));
+  Ok(())
+}
+
 /// `[$mul: lhs (any); rhs (any)]`
 ///
 /// Multiplies two values.
@@ -40,11 +108,62 @@ pub(crate) fn sub(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdRes
 /// `[$div: lhs (any); rhs (any)]`
 ///
 /// Divides one number by another.
+///
+/// If both values are integers (or booleans), the result is an integer rounded toward zero, so `[div: 1; 2]` is 0;
+/// otherwise, the result is a float. Use `[fdiv]` to always get a float.
+/// Dividing by an integer zero (or `false`) raises an error, while dividing by a float zero produces infinity or NaN.
 pub(crate) fn div(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdResult {
   vm.cur_frame_mut().write_value((lhs / rhs).into_runtime_result()?);
   Ok(())
 }
 
+/// `[$fdiv: lhs (any); rhs (any)]`
+///
+/// Divides one number by another and returns the result as a float, even if both values are integers.
+/// Division by zero is handled the same way as by `[div]`.
+pub(crate) fn fdiv(vm: &mut VM, (lhs, rhs): (RantValue, RantValue)) -> RantStdResult {
+  let lhs = match lhs {
+    RantValue::Integer(n) => RantValue::Float(n as f64),
+    RantValue::Boolean(b) => RantValue::Float(if b { 1.0 } else { 0.0 }),
+    other => other,
+  };
+  vm.cur_frame_mut().write_value((lhs / rhs).into_runtime_result()?);
+  Ok(())
+}
+
+/// `[$divmod: a (number); b (number)]`
+///
+/// Divides `a` by `b` and returns the quotient and remainder as a `(quotient; remainder)` list.
+/// Like `[div]` and `[mod]`, the quotient is rounded toward zero and the remainder has the same sign as `a`.
+/// If both values are integers, both results are integers; otherwise, both are floats. Raises an error if `b` is zero.
+pub(crate) fn divmod(vm: &mut VM, (a, b): (RantValue, RantValue)) -> RantStdResult {
+  let (quotient, remainder) = match (a, b) {
+    (_, RantValue::Integer(0)) => runtime_error!(RuntimeErrorType::ArgumentError, "cannot divide by zero"),
+    (RantValue::Integer(a), RantValue::Integer(b)) => match (a.checked_div(b), a.checked_rem(b)) {
+      (Some(q), Some(r)) => (RantValue::Integer(q), RantValue::Integer(r)),
+      _ => runtime_error!(RuntimeErrorType::ArgumentError, "division of {} by {} overflows", a, b),
+    },
+    (a, b) => {
+      let as_float = |val: &RantValue| match val {
+        RantValue::Integer(n) => Some(*n as f64),
+        RantValue::Float(n) => Some(*n),
+        _ => None,
+      };
+      let (a, b) = match (as_float(&a), as_float(&b)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => runtime_error!(RuntimeErrorType::ArgumentError, "cannot divide '{}' by '{}'", a.type_name(), b.type_name()),
+      };
+      if b == 0.0 {
+        runtime_error!(RuntimeErrorType::ArgumentError, "cannot divide by zero");
+      }
+      (RantValue::Float((a / b).trunc()), RantValue::Float(a % b))
+    },
+  };
+  let list = RantList::from(vec![quotient, remainder]);
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(list))));
+  Ok(())
+}
+
 /// `[$mod: lhs (any); rhs (any)]`
 ///
 /// Gets the modulus of two values.
@@ -69,14 +188,66 @@ pub(crate) fn recip(vm: &mut VM, val: RantValue) -> RantStdResult {
   Ok(())
 }
 
+/// `[$pow: base (integer|float); exp (integer|float)]`
+///
+/// Raises `base` to the power of `exp`.
+/// The result is an integer if both arguments are integers and `exp` is non-negative; otherwise, it is a float.
+/// Integer results saturate instead of overflowing.
+pub(crate) fn pow(vm: &mut VM, (base, exp): (RantValue, RantValue)) -> RantStdResult {
+  let result = match (base, exp) {
+    (RantValue::Integer(base), RantValue::Integer(exp)) if exp >= 0 => {
+      // Keep the parity of very large exponents so negative bases get the right sign
+      let exp = if exp > u32::MAX as i64 { u32::MAX - (exp % 2 == 0) as u32 } else { exp as u32 };
+      RantValue::Integer(base.saturating_pow(exp))
+    },
+    (RantValue::Integer(base), RantValue::Integer(exp)) => RantValue::Float((base as f64).powf(exp as f64)),
+    (RantValue::Integer(base), RantValue::Float(exp)) => RantValue::Float((base as f64).powf(exp)),
+    (RantValue::Float(base), RantValue::Integer(exp)) => RantValue::Float(base.powf(exp as f64)),
+    (RantValue::Float(base), RantValue::Float(exp)) => RantValue::Float(base.powf(exp)),
+    (base, exp) => runtime_error!(RuntimeErrorType::ArgumentError, "cannot raise '{}' value to '{}' value", base.type_name(), exp.type_name()),
+  };
+  vm.cur_frame_mut().write_value(result);
+  Ok(())
+}
+
+/// `[$sqrt: x (integer|float)]`
+///
+/// Gets the square root of `x` as a float. Raises an error if `x` is negative.
+pub(crate) fn sqrt(vm: &mut VM, x: RantValue) -> RantStdResult {
+  let x = match x {
+    RantValue::Integer(n) => n as f64,
+    RantValue::Float(n) => n,
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot take the square root of '{}' value", other.type_name()),
+  };
+  if x < 0.0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "cannot take the square root of negative number {}", x);
+  }
+  vm.cur_frame_mut().write_value(RantValue::Float(x.sqrt()));
+  Ok(())
+}
+
+/// `[$abs: val (integer|float)]`
+///
+/// Gets the absolute value of the specified value. The result has the same type as the input.
+pub(crate) fn abs(vm: &mut VM, val: RantValue) -> RantStdResult {
+  let val_result = match val {
+    RantValue::Float(f) => RantValue::Float(f.abs()),
+    RantValue::Integer(i) => RantValue::Integer(i.saturating_abs()),
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot use abs function on '{}' value", other.type_name())
+  };
+  vm.cur_frame_mut().write_value(val_result);
+  Ok(())
+}
+
 /// `[$floor: val (integer|float)]`
 ///
 /// Gets the largest integer that is less than or equal to the specified value.
+/// Floats that are non-finite or outside the integer range are returned as floats.
 pub(crate) fn floor(vm: &mut VM, val: RantValue) -> RantStdResult {
   let val_result = match val {
-    RantValue::Float(f) => RantValue::Float(f.floor()),
+    RantValue::Float(f) => float_to_int_if_exact(f.floor()),
     RantValue::Integer(i) => RantValue::Integer(i),
-    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot use floor function on '{}' value")
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot use floor function on '{}' value", other.type_name())
   };
   vm.cur_frame_mut().write_value(val_result);
   Ok(())
@@ -85,16 +256,42 @@ pub(crate) fn floor(vm: &mut VM, val: RantValue) -> RantStdResult {
 /// `[$ceil: val (integer|float)]`
 ///
 /// Gets the smallest integer that is greater than or equal to the specified value.
+/// Floats that are non-finite or outside the integer range are returned as floats.
 pub(crate) fn ceil(vm: &mut VM, val: RantValue) -> RantStdResult {
   let val_result = match val {
-    RantValue::Float(f) => RantValue::Float(f.ceil()),
+    RantValue::Float(f) => float_to_int_if_exact(f.ceil()),
     RantValue::Integer(i) => RantValue::Integer(i),
-    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot use ceil function on '{}' value")
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot use ceil function on '{}' value", other.type_name())
   };
   vm.cur_frame_mut().write_value(val_result);
   Ok(())
 }
 
+/// `[$round: val (integer|float)]`
+///
+/// Gets the integer nearest to the specified value. Halfway cases are rounded away from zero, so `2.5` becomes `3` and `-2.5` becomes `-3`.
+/// Floats that are non-finite or outside the integer range are returned as floats.
+pub(crate) fn round(vm: &mut VM, val: RantValue) -> RantStdResult {
+  let val_result = match val {
+    RantValue::Float(f) => float_to_int_if_exact(f.round()),
+    RantValue::Integer(i) => RantValue::Integer(i),
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot use round function on '{}' value", other.type_name())
+  };
+  vm.cur_frame_mut().write_value(val_result);
+  Ok(())
+}
+
+/// Converts a whole float to an integer, or keeps it as a float if it is non-finite or outside the range of `i64`.
+fn float_to_int_if_exact(f: f64) -> RantValue {
+  // 2^63 is exactly representable, so this covers every float that converts to i64 without saturating
+  const I64_BOUND: f64 = 9_223_372_036_854_775_808.0;
+  if f.is_finite() && (-I64_BOUND..I64_BOUND).contains(&f) {
+    RantValue::Integer(f as i64)
+  } else {
+    RantValue::Float(f)
+  }
+}
+
 /// `[$frac: val (float)]`
 ///
 /// Gets the fractional part of the specified float value.
@@ -8,4 +8,27 @@ pub(crate) fn proto(vm: &mut VM, map: RantMapRef) -> RantStdResult {
 pub(crate) fn set_proto(vm: &mut VM, (map, proto): (RantMapRef, Option<RantMapRef>)) -> RantStdResult {
   map.borrow_mut().set_proto(proto);
   Ok(())
+}
+
+/// `[$new: proto (map); init-map? (map)]`
+///
+/// Creates a new map with `proto` as its prototype, optionally initialized with the own entries of `init-map`.
+pub(crate) fn new(vm: &mut VM, (proto, init_map): (RantMapRef, Option<RantMapRef>)) -> RantStdResult {
+  let mut instance = RantMap::new();
+  if let Some(init_map) = init_map {
+    for (key, val) in init_map.borrow().raw_pairs() {
+      instance.raw_set(key, val.clone());
+    }
+  }
+  instance.set_proto(Some(proto));
+  vm.cur_frame_mut().write_value(RantValue::Map(Rc::new(RefCell::new(instance))));
+  Ok(())
+}
+
+/// `[$bind: map (map); func (function)]`
+///
+/// Returns a function that calls `func` with `map` as its first argument, followed by any arguments passed to it.
+pub(crate) fn bind(vm: &mut VM, (map, func): (RantMapRef, RantFunctionRef)) -> RantStdResult {
+  vm.cur_frame_mut().write_value(RantValue::Function(Rc::new(RantFunction::bind(RantValue::Map(map), func))));
+  Ok(())
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use std::iter::FromIterator;
+use std::{cmp::Reverse, iter::FromIterator};
 
 use super::*;
 
@@ -26,6 +26,23 @@ pub(crate) fn seg(vm: &mut VM, (s, seg_size): (String, usize)) -> RantStdResult
   Ok(())
 }
 
+/// `[$substring: s (string); start (int); length? (int)]`
+///
+/// Prints the part of `s` that starts at character index `start` and is `length` characters long, or extends to the end of `s` if `length` is omitted.
+/// Indices count characters, not bytes. An out-of-range `start` prints nothing, and a `length` that runs past the end of `s` is clamped.
+pub(crate) fn substring(vm: &mut VM, (s, start, length): (String, i64, Option<i64>)) -> RantStdResult {
+  if start < 0 {
+    return Ok(())
+  }
+  let chars = s.chars().skip(start as usize);
+  let sub: String = match length {
+    Some(length) => chars.take(length.max(0) as usize).collect(),
+    None => chars.collect(),
+  };
+  vm.cur_frame_mut().write_frag(sub.as_str());
+  Ok(())
+}
+
 pub(crate) fn split(vm: &mut VM, (s, at): (String, Option<String>)) -> RantStdResult {
   let list = if at.as_ref().map(|s| s.is_empty()).unwrap_or(true) {
     s.chars()
@@ -48,7 +65,270 @@ pub(crate) fn lines(vm: &mut VM, s: String) -> RantStdResult {
   Ok(())
 }
 
-pub(crate) fn indent(vm:  &mut VM, (text, indent): (String, String)) -> RantStdResult {
+/// `[$count-lines: s (string)]`
+///
+/// Returns the number of lines in a string, counting lines the same way as `[lines]`.
+/// A trailing line break does not start a new line, so `a\nb\n` has two lines and an empty string has none.
+pub(crate) fn count_lines(vm: &mut VM, s: String) -> RantStdResult {
+  vm.cur_frame_mut().write_value(RantValue::Integer(s.lines().count() as i64));
+  Ok(())
+}
+
+/// `[$count-words: s (string)]`
+///
+/// Returns the number of whitespace-separated words in a string.
+pub(crate) fn count_words(vm: &mut VM, s: String) -> RantStdResult {
+  vm.cur_frame_mut().write_value(RantValue::Integer(s.split_whitespace().count() as i64));
+  Ok(())
+}
+
+/// `[$sentences: text (string); abbreviations? (list)]`
+///
+/// Splits `text` into a list of sentences. A sentence ends at a `.`, `!`, or `?` followed by whitespace or the end of the text.
+/// Periods ending a known abbreviation (such as "Mr." or "e.g.") don't end a sentence; the default abbreviation list can be replaced by passing `abbreviations`.
+pub(crate) fn sentences(vm: &mut VM, (text, abbreviations): (String, Option<Vec<String>>)) -> RantStdResult {
+  const DEFAULT_ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "vs", "etc", "e.g", "i.e", "approx", "no",
+  ];
+
+  let abbreviations: Vec<String> = abbreviations
+    .map(|abbrs| abbrs.iter().map(|a| a.trim_end_matches('.').to_lowercase()).collect())
+    .unwrap_or_else(|| DEFAULT_ABBREVIATIONS.iter().map(|a| a.to_string()).collect());
+
+  let mut sentences = RantList::new();
+  let mut start = 0;
+  let mut chars = text.char_indices().peekable();
+  while let Some((i, c)) = chars.next() {
+    if !matches!(c, '.' | '!' | '?') {
+      continue
+    }
+
+    // Sentence terminators must be followed by whitespace or the end of the text
+    if chars.peek().map_or(false, |(_, next)| !next.is_whitespace()) {
+      continue
+    }
+
+    // Don't split after abbreviations
+    if c == '.' {
+      let last_word = text[start..i].rsplit(char::is_whitespace).next().unwrap_or_default().to_lowercase();
+      if abbreviations.contains(&last_word) {
+        continue
+      }
+    }
+
+    let end = i + c.len_utf8();
+    let sentence = text[start..end].trim();
+    if !sentence.is_empty() {
+      sentences.push(RantValue::String(sentence.to_owned()));
+    }
+    start = end;
+  }
+
+  let remainder = text[start..].trim();
+  if !remainder.is_empty() {
+    sentences.push(RantValue::String(remainder.to_owned()));
+  }
+
+  vm.cur_frame_mut().write_value(RantValue::List(Rc::new(RefCell::new(sentences))));
+  Ok(())
+}
+
+/// `[$replace-map: s (string); replacements (map)]`
+///
+/// Replaces every occurrence of each key in `replacements` with its value in a single left-to-right pass over `s`.
+/// Replaced text is never scanned again, and when several keys match at the same position, the longest one wins.
+pub(crate) fn replace_map(vm: &mut VM, (s, replacements): (String, RantMapRef)) -> RantStdResult {
+  let replacements = replacements.borrow();
+  let mut pairs: Vec<(String, String)> = replacements
+    .raw_keys()
+    .iter()
+    .map(|key| key.to_string())
+    .filter(|key| !key.is_empty())
+    .map(|key| {
+      let replacement = replacements.raw_get(key.as_str()).map(|val| val.to_string()).unwrap_or_default();
+      (key, replacement)
+    })
+    .collect();
+  pairs.sort_by_key(|(key, _)| Reverse(key.len()));
+
+  let mut replaced = String::with_capacity(s.len());
+  let mut rest = s.as_str();
+  while let Some(c) = rest.chars().next() {
+    if let Some((key, replacement)) = pairs.iter().find(|(key, _)| rest.starts_with(key.as_str())) {
+      replaced.push_str(replacement);
+      rest = &rest[key.len()..];
+    } else {
+      replaced.push(c);
+      rest = &rest[c.len_utf8()..];
+    }
+  }
+
+  vm.cur_frame_mut().write_frag(replaced.as_str());
+  Ok(())
+}
+
+/// `[$template: s (string); vars (map); strict? (bool)]`
+///
+/// Expands `${name}` placeholders in `s` with the matching values from `vars`, and `$$` to a literal `$`.
+/// The string is never run as Rant code.
+///
+/// Placeholders without a matching key are left intact, unless `strict` is true, in which case they raise an error.
+pub(crate) fn template(vm: &mut VM, (s, vars, strict): (String, RantMapRef, Option<bool>)) -> RantStdResult {
+  let vars = vars.borrow();
+  let strict = strict.unwrap_or(false);
+
+  let mut expanded = String::with_capacity(s.len());
+  let mut rest = s.as_str();
+  while let Some(dollar_pos) = rest.find('$') {
+    expanded.push_str(&rest[..dollar_pos]);
+    rest = &rest[dollar_pos..];
+
+    if rest.starts_with("$$") {
+      expanded.push('$');
+      rest = &rest[2..];
+      continue
+    }
+
+    if rest.starts_with("${") {
+      if let Some(close_pos) = rest.find('}') {
+        let name = &rest[2..close_pos];
+        match vars.raw_get(name) {
+          Some(val) => expanded.push_str(val.to_string().as_str()),
+          None if strict => runtime_error!(RuntimeErrorType::KeyError(KeyError::KeyNotFound(name.to_owned())), "template variable '{}' is not defined", name),
+          None => expanded.push_str(&rest[..=close_pos]),
+        }
+        rest = &rest[close_pos + 1..];
+        continue
+      }
+    }
+
+    expanded.push('$');
+    rest = &rest[1..];
+  }
+  expanded.push_str(rest);
+
+  vm.cur_frame_mut().write_frag(expanded.as_str());
+  Ok(())
+}
+
+/// `[$count-substr: haystack (string); needle (string)]`
+///
+/// Returns the number of non-overlapping occurrences of `needle` in `haystack`. An empty `needle` always counts as 0.
+pub(crate) fn count_substr(vm: &mut VM, (haystack, needle): (String, String)) -> RantStdResult {
+  let count = if needle.is_empty() { 0 } else { haystack.matches(needle.as_str()).count() };
+  vm.cur_frame_mut().write_value(RantValue::Integer(count as i64));
+  Ok(())
+}
+
+/// `[$starts-with: s (string); prefix (string)]`
+///
+/// Returns true if `s` begins with `prefix`. An empty `prefix` always matches.
+pub(crate) fn starts_with(vm: &mut VM, (s, prefix): (String, String)) -> RantStdResult {
+  vm.cur_frame_mut().write_value(RantValue::Boolean(s.starts_with(prefix.as_str())));
+  Ok(())
+}
+
+/// `[$ends-with: s (string); suffix (string)]`
+///
+/// Returns true if `s` ends with `suffix`. An empty `suffix` always matches.
+pub(crate) fn ends_with(vm: &mut VM, (s, suffix): (String, String)) -> RantStdResult {
+  vm.cur_frame_mut().write_value(RantValue::Boolean(s.ends_with(suffix.as_str())));
+  Ok(())
+}
+
+/// `[$repeat-str: s (string); n (int)]`
+///
+/// Prints `s` repeated `n` times. Raises an error if `n` is negative.
+pub(crate) fn repeat_str(vm: &mut VM, (s, n): (String, i64)) -> RantStdResult {
+  if n < 0 {
+    runtime_error!(RuntimeErrorType::ArgumentError, "repeat count cannot be negative (got {})", n);
+  }
+  let n = n as usize;
+  let capacity = match s.len().checked_mul(n) {
+    Some(capacity) => capacity,
+    None => runtime_error!(RuntimeErrorType::ArgumentError, "repeated string would be too long"),
+  };
+  let mut repeated = String::with_capacity(capacity);
+  for _ in 0..n {
+    repeated.push_str(s.as_str());
+  }
+  vm.cur_frame_mut().write_frag(repeated.as_str());
+  Ok(())
+}
+
+/// `[$char-index-map: alphabet (string)]`
+///
+/// Returns a map from each character in `alphabet` to its index. If a character appears more than once, its first index is used.
+pub(crate) fn char_index_map(vm: &mut VM, alphabet: String) -> RantStdResult {
+  let mut map = RantMap::new();
+  for (i, c) in alphabet.chars().enumerate() {
+    let key = c.to_string();
+    if !map.raw_has_key(key.as_str()) {
+      map.raw_set(key.as_str(), RantValue::Integer(i as i64));
+    }
+  }
+  vm.cur_frame_mut().write_value(RantValue::Map(Rc::new(RefCell::new(map))));
+  Ok(())
+}
+
+/// `[$rot: s (string); n (int)]`
+///
+/// Prints `s` with each ASCII letter shifted `n` places through the alphabet, preserving case. `n` may be negative.
+/// All other characters, including non-ASCII letters, are left unchanged.
+pub(crate) fn rot(vm: &mut VM, (s, n): (String, i64)) -> RantStdResult {
+  let shift = n.rem_euclid(26) as u8;
+  let rotated: String = s.chars().map(|c| {
+    let base = match c {
+      'a'..='z' => b'a',
+      'A'..='Z' => b'A',
+      _ => return c,
+    };
+    ((c as u8 - base + shift) % 26 + base) as char
+  }).collect();
+  vm.cur_frame_mut().write_frag(rotated.as_str());
+  Ok(())
+}
+
+/// `[$matches-glob: s (string); pattern (string)]`
+///
+/// Returns `true` if the whole of `s` matches the glob `pattern`; otherwise, returns `false`.
+///
+/// Supported wildcards:
+/// * `*` matches any sequence of characters, including an empty one
+/// * `?` matches any single character
+/// * `[abc]` matches any one of the listed characters; ranges such as `[a-z]` are allowed, and `[!abc]` negates the class
+///
+/// Any other character matches itself. A `[` without a closing `]` is treated as a literal.
+pub(crate) fn matches_glob(vm: &mut VM, (s, pattern): (String, String)) -> RantStdResult {
+  let pattern = parse_glob(pattern.as_str());
+  let text: Vec<char> = s.chars().collect();
+  vm.cur_frame_mut().write_value(RantValue::Boolean(glob_match(&pattern, &text)));
+  Ok(())
+}
+
+/// `[$unlines: lines (list); trailing-newline? (bool)]`
+///
+/// Prints the elements of `lines` joined by line breaks. This is the inverse of `[lines]`.
+/// No trailing line break is printed unless `trailing-newline` is true.
+pub(crate) fn unlines(vm: &mut VM, (lines, trailing_newline): (Vec<RantValue>, Option<bool>)) -> RantStdResult {
+  let mut text = lines.iter().map(|line| line.to_string()).collect::<Vec<String>>().join("\n");
+  if trailing_newline.unwrap_or(false) {
+    text.push('\n');
+  }
+  vm.cur_frame_mut().write_frag(text.as_str());
+  Ok(())
+}
+
+/// `[$indent: text (string); indent? (string); count? (int); skip-blank? (bool)]`
+///
+/// Prints `text` with each line prefixed by `indent` repeated `count` times (default: 1).
+/// If `indent` is omitted, four spaces are used.
+///
+/// If `skip-blank` is true, lines that are empty or contain only whitespace are left unindented.
+pub(crate) fn indent(vm:  &mut VM, (text, indent, count, skip_blank): (String, Option<String>, Option<usize>, Option<bool>)) -> RantStdResult {
+  const DEFAULT_INDENT: &str = "    ";
+  let prefix = indent.as_deref().unwrap_or(DEFAULT_INDENT).repeat(count.unwrap_or(1));
+  let skip_blank = skip_blank.unwrap_or(false);
   let frame = vm.cur_frame_mut();
   let mut first = true;
   for line in text.lines() {
@@ -57,12 +337,86 @@ pub(crate) fn indent(vm:  &mut VM, (text, indent): (String, String)) -> RantStdR
     } else {
       frame.write_frag("\n");
     }
-    frame.write_frag(indent.as_str());
+    if !(skip_blank && line.trim().is_empty()) {
+      frame.write_frag(prefix.as_str());
+    }
     frame.write_frag(line);
   }
   Ok(())
 }
 
+/// `[$dedent: text (string)]`
+///
+/// Prints `text` with the longest leading-whitespace prefix common to all of its non-blank lines removed from each line.
+/// Blank lines don't affect the common prefix and are printed empty.
+pub(crate) fn dedent(vm: &mut VM, text: String) -> RantStdResult {
+  let common_prefix = text
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .map(|line| &line[..line.len() - line.trim_start().len()])
+    .fold(None, |prefix: Option<&str>, indent| Some(match prefix {
+      Some(prefix) => {
+        let common_len = prefix
+          .char_indices()
+          .zip(indent.chars())
+          .find(|((_, a), b)| a != b)
+          .map_or(prefix.len().min(indent.len()), |((i, _), _)| i);
+        &prefix[..common_len]
+      },
+      None => indent,
+    }))
+    .unwrap_or_default();
+
+  let frame = vm.cur_frame_mut();
+  let mut first = true;
+  for line in text.lines() {
+    if first {
+      first = false;
+    } else {
+      frame.write_frag("\n");
+    }
+    if !line.trim().is_empty() {
+      frame.write_frag(&line[common_prefix.len()..]);
+    }
+  }
+  Ok(())
+}
+
+/// `[$trim-lines: text (string)]`
+///
+/// Prints `text` with trailing whitespace removed from each line and leading and trailing blank lines removed.
+pub(crate) fn trim_lines(vm: &mut VM, text: String) -> RantStdResult {
+  let lines: Vec<&str> = text.lines().map(|line| line.trim_end()).collect();
+  let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+  let end = lines.iter().rposition(|line| !line.is_empty()).map_or(start, |i| i + 1);
+  vm.cur_frame_mut().write_frag(lines[start..end].join("\n").as_str());
+  Ok(())
+}
+
+/// `[$trim: s (string)]`
+///
+/// Prints `s` with leading and trailing whitespace removed.
+pub(crate) fn trim(vm: &mut VM, s: String) -> RantStdResult {
+  vm.cur_frame_mut().write_frag(s.trim());
+  Ok(())
+}
+
+/// `[$trim-start: s (string)]`
+///
+/// Prints `s` with leading whitespace removed.
+pub(crate) fn trim_start(vm: &mut VM, s: String) -> RantStdResult {
+  vm.cur_frame_mut().write_frag(s.trim_start());
+  Ok(())
+}
+
+/// `[$trim-end: s (string)]`
+///
+/// Prints `s` with trailing whitespace removed.
+pub(crate) fn trim_end(vm: &mut VM, s: String) -> RantStdResult {
+  vm.cur_frame_mut().write_frag(s.trim_end());
+  Ok(())
+}
+
 pub(crate) fn upper(vm: &mut VM, s: String) -> RantStdResult {
   vm.cur_frame_mut().write_frag(s.to_uppercase().as_str());
   Ok(())
@@ -71,4 +425,165 @@ pub(crate) fn upper(vm: &mut VM, s: String) -> RantStdResult {
 pub(crate) fn lower(vm: &mut VM, s: String) -> RantStdResult {
   vm.cur_frame_mut().write_frag(s.to_lowercase().as_str());
   Ok(())
+}
+
+/// `[$capitalize: s (string)]`
+///
+/// Prints `s` with its first character in uppercase. The rest of the string is left unchanged, so "mcDonald" becomes "McDonald".
+pub(crate) fn capitalize(vm: &mut VM, s: String) -> RantStdResult {
+  vm.cur_frame_mut().write_frag(capitalize_word(s.as_str()).as_str());
+  Ok(())
+}
+
+/// `[$title-case: s (string)]`
+///
+/// Prints `s` with the first letter of each whitespace-delimited word in uppercase. Other letters and all whitespace are left unchanged.
+pub(crate) fn title_case(vm: &mut VM, s: String) -> RantStdResult {
+  let mut titled = String::with_capacity(s.len());
+  let mut word_start = true;
+  for c in s.chars() {
+    if word_start && !c.is_whitespace() {
+      titled.extend(c.to_uppercase());
+    } else {
+      titled.push(c);
+    }
+    word_start = c.is_whitespace();
+  }
+  vm.cur_frame_mut().write_frag(titled.as_str());
+  Ok(())
+}
+
+/// `[$smart-title: s (string); small-words? (list)]`
+///
+/// Prints `s` in title case, but keeps small words (articles, conjunctions, and short prepositions) lowercase
+/// unless they are the first or last word. The default small-word list can be replaced by passing `small-words`.
+/// Like `[title-case]`, only the first letter of other words is changed, so acronyms such as "FBI" keep their case.
+pub(crate) fn smart_title(vm: &mut VM, (s, small_words): (String, Option<Vec<String>>)) -> RantStdResult {
+  const DEFAULT_SMALL_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "in", "nor", "of", 
+    "off", "on", "or", "per", "so", "the", "to", "up", "via", "yet",
+  ];
+
+  let small_words: Vec<String> = small_words
+    .map(|words| words.iter().map(|w| w.to_lowercase()).collect())
+    .unwrap_or_else(|| DEFAULT_SMALL_WORDS.iter().map(|w| w.to_string()).collect());
+
+  let words: Vec<&str> = s.split(' ').collect();
+  let first_word = words.iter().position(|w| !w.is_empty());
+  let last_word = words.iter().rposition(|w| !w.is_empty());
+
+  let titled = words
+    .iter()
+    .enumerate()
+    .map(|(i, word)| {
+      let lower = word.to_lowercase();
+      if Some(i) != first_word && Some(i) != last_word && small_words.contains(&lower) {
+        lower
+      } else {
+        capitalize_word(word)
+      }
+    })
+    .collect::<Vec<String>>()
+    .join(" ");
+
+  vm.cur_frame_mut().write_frag(titled.as_str());
+  Ok(())
+}
+
+/// Uppercases the first character of a word and leaves the rest unchanged.
+fn capitalize_word(word: &str) -> String {
+  let mut chars = word.chars();
+  match chars.next() {
+    Some(first) => first.to_uppercase().chain(chars).collect(),
+    None => String::new(),
+  }
+}
+
+enum GlobToken {
+  Star,
+  AnyChar,
+  Char(char),
+  Class { negated: bool, ranges: Vec<(char, char)> },
+}
+
+impl GlobToken {
+  fn matches(&self, c: char) -> bool {
+    match self {
+      GlobToken::Star | GlobToken::AnyChar => true,
+      GlobToken::Char(expected) => *expected == c,
+      GlobToken::Class { negated, ranges } => ranges.iter().any(|(lo, hi)| (*lo..=*hi).contains(&c)) != *negated,
+    }
+  }
+}
+
+fn parse_glob(pattern: &str) -> Vec<GlobToken> {
+  let chars: Vec<char> = pattern.chars().collect();
+  let mut tokens = vec![];
+  let mut i = 0;
+  while i < chars.len() {
+    match chars[i] {
+      '*' => tokens.push(GlobToken::Star),
+      '?' => tokens.push(GlobToken::AnyChar),
+      '[' => {
+        let negated = chars.get(i + 1) == Some(&'!');
+        let class_start = if negated { i + 2 } else { i + 1 };
+        // A ']' directly after the opening bracket is part of the class
+        let class_end = chars.iter().skip(class_start + 1).position(|c| *c == ']').map(|pos| pos + class_start + 1);
+        if let Some(class_end) = class_end {
+          let mut ranges = vec![];
+          let mut j = class_start;
+          while j < class_end {
+            if j + 2 < class_end && chars[j + 1] == '-' {
+              ranges.push((chars[j], chars[j + 2]));
+              j += 3;
+            } else {
+              ranges.push((chars[j], chars[j]));
+              j += 1;
+            }
+          }
+          tokens.push(GlobToken::Class { negated, ranges });
+          i = class_end;
+        } else {
+          tokens.push(GlobToken::Char('['));
+        }
+      },
+      c => tokens.push(GlobToken::Char(c)),
+    }
+    i += 1;
+  }
+  tokens
+}
+
+fn glob_match(pattern: &[GlobToken], text: &[char]) -> bool {
+  let (mut p, mut t) = (0, 0);
+  // Position of the last star in the pattern and the text position it's currently matched up to
+  let mut backtrack: Option<(usize, usize)> = None;
+
+  while t < text.len() {
+    match pattern.get(p) {
+      Some(GlobToken::Star) => {
+        backtrack = Some((p, t));
+        p += 1;
+        continue
+      },
+      Some(token) if token.matches(text[t]) => {
+        p += 1;
+        t += 1;
+        continue
+      },
+      _ => {}
+    }
+
+    // Let the last star consume one more character and try again
+    match backtrack {
+      Some((star_p, star_t)) => {
+        p = star_p + 1;
+        t = star_t + 1;
+        backtrack = Some((star_p, star_t + 1));
+      },
+      None => return false,
+    }
+  }
+
+  pattern[p..].iter().all(|token| matches!(token, GlobToken::Star))
 }
\ No newline at end of file
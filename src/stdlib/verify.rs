@@ -46,6 +46,19 @@ pub(crate) fn is_number(vm: &mut VM, value: RantValue) -> RantStdResult {
   Ok(())
 }
 
+/// `[$is-whole: value (any)]`
+///
+/// Returns `true` if `value` is an integer or a finite float with no fractional part; otherwise, returns `false`.
+pub(crate) fn is_whole(vm: &mut VM, value: RantValue) -> RantStdResult {
+  let is_whole = match value {
+    RantValue::Integer(_) => true,
+    RantValue::Float(n) => n.is_finite() && n.fract() == 0.0,
+    _ => false,
+  };
+  vm.cur_frame_mut().write_value(RantValue::Boolean(is_whole));
+  Ok(())
+}
+
 pub(crate) fn is_between(vm: &mut VM, (value, a, b): (RantValue, RantValue, RantValue)) -> RantStdResult {
   let (a, b) = util::minmax(a, b);
   let result = value >= a && value <= b;
@@ -58,6 +71,25 @@ pub(crate) fn is(vm: &mut VM, (value, type_name): (RantValue, String)) -> RantSt
   Ok(())
 }
 
+/// `[$is-cyclic: value (any)]`
+///
+/// Returns true if `value` is a list or map that contains itself, either directly or through other nested lists and maps.
+pub(crate) fn is_cyclic(vm: &mut VM, value: RantValue) -> RantStdResult {
+  vm.cur_frame_mut().write_value(RantValue::Boolean(has_cycle(&value)));
+  Ok(())
+}
+
+/// `[$expect-type: value (any); type-name (string)]`
+///
+/// Returns `value` unchanged if its type is `type-name`; otherwise, raises a runtime error.
+pub(crate) fn expect_type(vm: &mut VM, (value, type_name): (RantValue, String)) -> RantStdResult {
+  if value.type_name() != type_name {
+    runtime_error!(RuntimeErrorType::TypeError, "expected value of type '{}', but found '{}'", type_name, value.type_name());
+  }
+  vm.cur_frame_mut().write_value(value);
+  Ok(())
+}
+
 pub(crate) fn is_empty(vm: &mut VM, value: RantValue) -> RantStdResult {
   vm.cur_frame_mut().write_value(RantValue::Boolean(value.is_empty()));
   Ok(())
@@ -76,4 +108,21 @@ pub(crate) fn is_bool(vm: &mut VM, value: RantValue) -> RantStdResult {
 pub(crate) fn is_nan(vm: &mut VM, value: RantValue) -> RantStdResult {
   vm.cur_frame_mut().write_value(RantValue::Boolean(value.is_nan()));
   Ok(())
+}
+
+/// `[$contains: haystack (string|list|map); needle (any)]`
+///
+/// Returns `true` if `haystack` contains `needle`:
+/// * strings: `needle` (as a string) is a substring of `haystack`
+/// * lists: any element of `haystack` is equal to `needle`, using the same equality as `[eq]`
+/// * maps: `needle` (as a string) is one of the map's own keys
+pub(crate) fn contains(vm: &mut VM, (haystack, needle): (RantValue, RantValue)) -> RantStdResult {
+  let result = match haystack {
+    RantValue::String(s) => s.contains(needle.to_string().as_str()),
+    RantValue::List(list) => list.borrow().contains(&needle),
+    RantValue::Map(map) => map.borrow().raw_has_key(needle.to_string().as_str()),
+    other => runtime_error!(RuntimeErrorType::ArgumentError, "cannot check membership in '{}' value", other.type_name()),
+  };
+  vm.cur_frame_mut().write_value(RantValue::Boolean(result));
+  Ok(())
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use crate::{lang::{Block, Parameter, Sequence}, lang::Identifier, RantVar};
+use crate::{lang::{Block, Parameter, PrintFlag, Sequence}, lang::Identifier, RantVar};
 use crate::runtime::*;
 use crate::{collections::*, util::*, IntoRuntimeResult, RuntimeResult, RuntimeError, RuntimeErrorType, stdlib::RantStdResult};
 use std::{fmt::{Display, Debug}, rc::Rc, ops::{Add, Not, Sub, Neg, Mul, Div, Rem}, cmp, cell::RefCell};
@@ -6,6 +6,7 @@ use std::mem;
 use std::error::Error;
 use cast::*;
 use resolver::SelectorRef;
+use crate::random::RantRngState;
 use cmp::Ordering;
 
 /// Adds a barebones `Error` implementation to the specified type.
@@ -154,6 +155,8 @@ impl RantValue {
       RantValue::List(lst) => lst.borrow().len(),
       // Length of map is element count
       RantValue::Map(map) => map.borrow().raw_len(),
+      // Empty has nothing in it
+      RantValue::Empty => 0,
       // Treat everything else as length 1, since all other value types are primitives
       _ => 1
     }
@@ -170,6 +173,16 @@ impl RantValue {
     }
   }
 
+  /// Returns a deep copy of the value, recursively copying any nested lists and maps.
+  /// Map prototypes are shared with the original.
+  pub fn deep_copy(&self) -> Self {
+    match self {
+      RantValue::List(list) => RantValue::List(Rc::new(RefCell::new(list.borrow().iter().map(RantValue::deep_copy).collect()))),
+      RantValue::Map(map) => RantValue::Map(Rc::new(RefCell::new(map.borrow().deep_copy()))),
+      _ => self.shallow_copy(),
+    }
+  }
+
   /// Gets the Rant type associated with the value.
   #[inline]
   pub fn get_type(&self) -> RantValueType {
@@ -213,11 +226,11 @@ impl RantValue {
     let uindex = uindex as usize;
 
     match self {
+      // Strings are indexed by character, consistent with their length
       RantValue::String(s) => {
-        if uindex < s.len() {
-          Ok(RantValue::String(s[uindex..uindex + 1].to_owned()))
-        } else {
-          Err(IndexError::OutOfRange)
+        match s.chars().nth(uindex) {
+          Some(c) => Ok(RantValue::String(c.to_string())),
+          None => Err(IndexError::OutOfRange)
         }
       },
       RantValue::List(list) => {
@@ -234,6 +247,12 @@ impl RantValue {
 
   /// Attempts to set a value by index.
   pub fn index_set(&mut self, index: i64, val: RantValue) -> ValueIndexSetResult {
+    // Maps use the index as a key, so it isn't counted from the end
+    if let RantValue::Map(map) = self {
+      map.borrow_mut().raw_set(index.to_string().as_str(), val);
+      return Ok(())
+    }
+
     let uindex = self.get_uindex(index);
 
     if uindex < 0 {
@@ -253,11 +272,6 @@ impl RantValue {
           Err(IndexError::OutOfRange)
         }
       },
-      RantValue::Map(map) => {
-        let mut map = map.borrow_mut();
-        map.raw_set(uindex.to_string().as_str(), val);
-        Ok(())
-      },
       _ => Err(IndexError::CannotSetIndexOnType(self.get_type()))
     }
   }
@@ -267,9 +281,8 @@ impl RantValue {
     match self {
       RantValue::Map(map) => {
         let map = map.borrow();
-        // TODO: Use prototype getter here
-        if let Some(val) = map.raw_get(key) {
-          Ok(val.clone())
+        if let Some(val) = map.get(key) {
+          Ok(val)
         } else {
           Err(KeyError::KeyNotFound(key.to_owned()))
         }
@@ -481,12 +494,15 @@ impl IntoRuntimeResult<()> for ValueKeySetResult {
 #[derive(Debug, Clone)]
 pub enum RantSpecial {
   Selector(SelectorRef),
+  RngState(Rc<RantRngState>),
 }
 
 impl PartialEq for RantSpecial {
   fn eq(&self, other: &Self) -> bool {
     match (self, other) {
       (RantSpecial::Selector(a), RantSpecial::Selector(b)) => a.as_ptr() == b.as_ptr(),
+      (RantSpecial::RngState(a), RantSpecial::RngState(b)) => Rc::ptr_eq(a, b),
+      _ => false,
     }
   }
 }
@@ -513,6 +529,36 @@ impl RantFunction {
   pub fn is_native(&self) -> bool {
     matches!(self.body, RantFunctionInterface::Foreign(_))
   }
+
+  /// Creates a function that calls `func` with `this` prepended to its arguments.
+  pub(crate) fn bind(this: RantValue, func: RantFunctionRef) -> Self {
+    // A variadic first parameter also absorbs the remaining arguments, so it stays in the signature
+    let params = match func.params.first() {
+      Some(first) if !first.varity.is_variadic() => func.params.iter().skip(1).cloned().collect(),
+      _ => func.params.to_vec(),
+    };
+    let min_arg_count = func.min_arg_count.saturating_sub(1);
+    let vararg_start_index = func.vararg_start_index.saturating_sub(1);
+
+    let body = RantFunctionInterface::Foreign(Rc::new(move |vm: &mut VM, args: Vec<RantValue>| {
+      let argc = args.len() + 1;
+      vm.push_val(RantValue::Function(Rc::clone(&func)))?;
+      for arg in args.into_iter().rev() {
+        vm.push_val(arg)?;
+      }
+      vm.push_val(this.clone())?;
+      vm.cur_frame_mut().push_intent_front(Intent::Call { argc, flag: PrintFlag::None, override_print: false });
+      Ok(())
+    }));
+
+    Self {
+      params: Rc::new(params),
+      min_arg_count,
+      vararg_start_index,
+      captured_vars: vec![],
+      body,
+    }
+  }
 }
 
 /// Defines endpoint variants for Rant functions.
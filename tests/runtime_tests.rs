@@ -28,6 +28,14 @@ macro_rules! test_rant {
   }}
 }
 
+macro_rules! test_rant_err {
+  ($src:literal, $expected:pat) => {{
+    let mut r = Rant::new();
+    let pgm = r.compile_quiet($src).expect("failed to compile program");
+    assert_matches!(r.run_into_string(&pgm).map_err(|err| err.error_type), Err($expected));
+  }}
+}
+
 #[test]
 fn empty_program() {
   test_rant!("", "");
@@ -357,4 +365,957 @@ fn getter_fallback_from_key() {
     "sources/getter_fallback_from_key.rant",
     "foo, bar, baz, oops"
   )
+}
+
+#[test]
+fn shuffled_preserves_original() {
+  test_rant!(r#"<$a=(1;2;3;4;5)><$b=[shuffled:<a>]>[join:,\s;<a>]"#, "1, 2, 3, 4, 5");
+}
+
+#[test]
+fn shuffle_reproducible_with_seed() {
+  let mut r = Rant::with_seed(0xc0ffee);
+  let pgm = r.compile_quiet(r#"<$a=(1;2;3;4;5;6;7;8)>[shuffle:<a>][join:,\s;<a>]"#).expect("failed to compile program");
+  let first = r.run_into_string(&pgm).expect("failed to run program");
+  assert_eq!(first, "4, 7, 1, 2, 3, 8, 6, 5");
+  r.reset_seed();
+  let second = r.run_into_string(&pgm).expect("failed to run program");
+  assert_eq!(first, second);
+}
+
+#[test]
+fn interleave_lists() {
+  test_rant!(r#"[join:,\s;[interleave:(1;2;3);(a;b);(x;y;z;w)]]"#, "1, a, x, 2, b, y, 3, z, w");
+}
+
+#[test]
+fn gather_indices() {
+  test_rant!(r#"[join:,\s;[gather:(a;b;c;d);(3;0;-1;1)]]"#, "d, a, d, b");
+}
+
+#[test]
+fn sort_indices_stable() {
+  test_rant!(r#"[join:,\s;[sort-indices:(3;1;2;1)]]"#, "1, 3, 2, 0");
+}
+
+#[test]
+fn sort_indices_by_co_sorts_lists() {
+  test_rant_file!(
+    "sources/sort_indices_by_co_sorts_lists.rant",
+    "cal, amy, bob, dan"
+  )
+}
+
+#[test]
+fn memoize_caches_results() {
+  test_rant_file!(
+    "sources/memoize_caches_results.rant",
+    "4 4 6 2"
+  );
+}
+
+#[test]
+fn stack_depth_increases_in_calls() {
+  test_rant!(r#"[$f]{[stack-depth]}[$g]{[f]}[sub:[g];[f]]"#, "1");
+}
+
+#[test]
+fn expect_type_passes_matching_value() {
+  test_rant!(r#"[expect-type:123;integer]"#, "123");
+}
+
+#[test]
+fn expect_type_rejects_mismatched_value() {
+  test_rant_err!(r#"[expect-type:foo;integer]"#, RuntimeErrorType::TypeError);
+}
+
+#[test]
+fn clone_shallow_copy_deep() {
+  test_rant_file!(
+    "sources/clone_shallow_copy_deep.rant",
+    "x 1 3"
+  );
+}
+
+#[test]
+fn map_counts_exclude_proto() {
+  test_rant_file!(
+    "sources/map_counts_exclude_proto.rant",
+    "2 2 2 2"
+  );
+}
+
+#[test]
+fn smart_title_keeps_small_words_lowercase() {
+  test_rant!(r#"[smart-title:"the lord OF the rings"]"#, "The Lord of the Rings");
+}
+
+#[test]
+fn smart_title_keeps_acronyms() {
+  test_rant!(r#"[smart-title:"NASA and the FBI meet mcDonald"]"#, "NASA and the FBI Meet McDonald");
+}
+
+#[test]
+fn smart_title_custom_small_words() {
+  test_rant!(r#"[smart-title:"a tale of two cities";(of;two)]"#, "A Tale of two Cities");
+}
+
+#[test]
+fn sentences_skip_abbreviations() {
+  test_rant!(r#"[join:" | ";[sentences:"Mr. Smith arrived. Did he stay?  He left, e.g. at noon!"]]"#, "Mr. Smith arrived. | Did he stay? | He left, e.g. at noon!");
+}
+
+#[test]
+fn replace_map_single_pass() {
+  test_rant!(r#"<$r=@(a=b;b=c;ab=X)>[replace-map:"abba cab";<r>]"#, "Xcb cX");
+}
+
+#[test]
+fn template_expands_placeholders() {
+  test_rant!(r#"<$vars=@(name=Bob;item=apple)>[template:"${name} paid $$5 for an ${item}, not ${other}";<vars>]"#, "Bob paid $5 for an apple, not ${other}");
+}
+
+#[test]
+fn matches_glob_wildcards() {
+  test_rant!(r#"[matches-glob:"report-2021.txt";"*-20??.txt"]\s[matches-glob:cat;"[bc]at"]\s[matches-glob:hat;"[!bc]at"]\s[matches-glob:cats;"c*t"]"#, "true true true false");
+}
+
+#[test]
+fn count_substr_non_overlapping() {
+  test_rant!(r#"[count-substr:aaaa;aa]\s[count-substr:"a, b, c";","]\s[count-substr:abc;""]"#, "2 2 0");
+}
+
+#[test]
+fn whitespace_policy_option() {
+  fn run_with_policy(policy: OutputWhitespacePolicy) -> String {
+    let mut r = Rant::with_options(RantOptions {
+      whitespace_policy: policy,
+      .. Default::default()
+    });
+    let pgm = r.compile_quiet(r#"{\s\sfoo\s\s} \s\sbar\s"#).expect("failed to compile program");
+    r.run_into_string(&pgm).expect("failed to run program")
+  }
+
+  assert_eq!(run_with_policy(OutputWhitespacePolicy::Collapse), " foo bar ");
+  assert_eq!(run_with_policy(OutputWhitespacePolicy::Trim), "foo bar");
+}
+
+#[test]
+fn explicit_whitespace_helpers() {
+  test_rant!(r#"[whitespace-fmt:ignore-all]a[space:3]b[tab]c[nl]d[space]e"#, "a   b\tc\nd e");
+}
+
+#[test]
+fn indent_with_options() {
+  test_rant!(r#"[indent:a\n\nb;\t;2;true]"#, "\t\ta\n\n\t\tb");
+}
+
+#[test]
+fn dedent_common_prefix() {
+  test_rant!(r#"[dedent:\s\s\s\sfoo\n\s\n\s\s\s\s\s\sbar\n\s\s\s\sbaz]"#, "foo\n\n  bar\nbaz");
+}
+
+#[test]
+fn unlines_round_trips_lines() {
+  test_rant!(r#"[unlines:[map:[lines:a\nb\nc];[?:line]{[upper:<line>]}]]\s[unlines:(x;y);true]"#, "A\nB\nC x\ny\n");
+}
+
+#[test]
+fn pad_list_to_length() {
+  test_rant!(r#"<$a=(1;2)><$b=(1;2;3;4)>[pad-list:<a>;4;x][pad-list:<b>;2;x][join:,\s;<a>]\s-\s[join:,\s;<b>]"#, "1, 2, x, x - 1, 2, 3, 4");
+}
+
+#[test]
+fn zip_longest_fills_missing() {
+  test_rant!(r#"[join:\s;[map:[zip-longest:(1;2;3);(a);-];[?:pair]{[join:"+";<pair>]}]]"#, "1+a 2+- 3+-");
+}
+
+#[test]
+fn group_runs_consecutive() {
+  test_rant!(r#"[join:\s;[map:[group-runs:(a;a;b;a;a;a)];[?:run]{[join:"";<run>]}]]\s[len:[group-runs:(1;3;5;2;4;7);[?:n]{[is-odd:<n>]}]]"#, "aa b aaa 3");
+}
+
+#[test]
+fn sum_and_mean_map_values() {
+  test_rant!(r#"<$m=@(a=1;b=2;c=3;d=6)><$n=@(a=1;b=foo;c=2)>[sum-values:<m>]\s[mean-values:<m>]\s[sum-values:<n>;true]"#, "12 3 3");
+}
+
+#[test]
+fn value_stack_limit_option() {
+  let mut r = Rant::with_options(RantOptions {
+    value_stack_limit: 2,
+    .. Default::default()
+  });
+  let pgm = r.compile_quiet(r#"[add:1;2]"#).expect("failed to compile program");
+  assert_matches!(r.run_into_string(&pgm).map_err(|err| err.error_type), Err(RuntimeErrorType::ValueStackOverflow));
+}
+
+#[test]
+fn is_whole_numbers() {
+  test_rant!(r#"[is-whole:3]\s[is-whole:3.0]\s[is-whole:3.5]\s[is-whole:[div:0.0;0.0]]\s[is-whole:foo]"#, "true true false false false");
+}
+
+#[test]
+fn num_str_prose_friendly() {
+  test_rant!(r#"[num-str:3.0]\s[num-str:2.50]\s[num-str:3.14159;2]\s[num-str:1.999;2]\s[num-str:42]"#, "3 2.5 3.14 2 42");
+}
+
+#[test]
+fn abs_diff_and_distance() {
+  test_rant!(r#"[abs-diff:3;10]\s[abs-diff:2.5;1]\s[distance:(0;0);(3;4)]"#, "7 1.5 5");
+}
+
+#[test]
+fn distance_mismatched_lengths() {
+  test_rant_err!(r#"[distance:(1;2);(1)]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn clamp01_and_percent() {
+  test_rant!(r#"[clamp01:1.5]\s[clamp01:-2]\s[percent:0.25]\s[percent:1.5]\s[percent:[clamp01:1.5]]\s[percent:0.12345;1]"#, "1 0 25% 150% 100% 12.3%");
+}
+
+#[test]
+fn remap_linear() {
+  test_rant!(r#"[remap:5;0;10;100;200]\s[remap:15;0;10;0;1]\s[remap:15;0;10;0;1;true]\s[remap:-5;0;10;1;0;true]"#, "150 1.5 1 1");
+}
+
+#[test]
+fn remap_zero_width_range() {
+  test_rant_err!(r#"[remap:1;2;2;0;1]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn snap_to_step() {
+  test_rant!(r#"[snap:1.23;0.05]\s[snap:17;5]\s[snap:17;5;1]\s[snap:-7;4]"#, "1.25 15 16 -8");
+}
+
+#[test]
+fn snap_zero_step() {
+  test_rant_err!(r#"[snap:1;0]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn count_lines_and_words() {
+  test_rant!(r#"[count-lines:a\nb\n]\s[count-lines:a\n\nb]\s[count-lines:""]\s[count-words:"  the quick  brown fox "]"#, "2 3 0 4");
+}
+
+#[test]
+fn trim_lines_cleanup() {
+  test_rant!(r#"[trim-lines:\s\n\n\sfoo\s\s\n\nbar\t\n\s\n]"#, " foo\n\nbar");
+}
+
+#[test]
+fn collapse_blanks_runs() {
+  test_rant!(r#"[collapse-blanks:a\n\n\n\nb\n\s\n\nc]\s[collapse-blanks:x\n\n\n\ny;2]\s[collapse-blanks:p\n\n\nq;0]"#, "a\n\nb\n \nc x\n\n\ny p\nq");
+}
+
+#[test]
+fn set_output_captures_block() {
+  test_rant!(r#"[rep:3][sep:-][set-output:x]{a}[upper:<x>]\s<x>"#, "A-A-A a-a-a");
+}
+
+#[test]
+fn profiling_counts_blocks_and_calls() {
+  let mut r = Rant::with_options(RantOptions {
+    enable_profiling: true,
+    debug_mode: true,
+    .. Default::default()
+  });
+  let pgm = r.compile_quiet(r#"[rep:3]{[add:1;1]}"#).expect("failed to compile program");
+  r.run_into_string(&pgm).expect("failed to run program");
+  let profile = r.last_profile().expect("no profile collected");
+  assert_eq!(profile.total(ProfiledElement::Block), 1);
+  assert_eq!(profile.total(ProfiledElement::FunctionCall), 4);
+  assert_eq!(profile.hot_spots()[0].1, 3);
+
+  let mut r = Rant::new();
+  r.run_into_string(&pgm).expect("failed to run program");
+  assert!(r.last_profile().is_none());
+}
+
+#[test]
+fn rng_save_and_restore() {
+  test_rant!(r#"<$s=[rng-save]><$a=[rand:1;1000000]>[rng-restore:<s>]<$b=[rand:1;1000000]>[eq:<a>;<b>]"#, "true");
+}
+
+#[test]
+fn env_reads_host_values() {
+  let mut environment = RantMap::new();
+  environment.raw_set("name", RantValue::String("world".to_owned()));
+  let mut r = Rant::with_options(RantOptions {
+    environment,
+    .. Default::default()
+  });
+  let pgm = r.compile_quiet(r#"[env:name]\s[is-empty:[env:missing]]"#).expect("failed to compile program");
+  assert_eq!(r.run_into_string(&pgm).expect("failed to run program"), "world true");
+}
+
+#[test]
+fn with_index_and_with_key_copy() {
+  test_rant!(r#"<$a=(1;2;3)><$b=[with-index:<a>;-1;x]>[join:,;<a>]\s[join:,;<b>]\s<$m=@(k=1)><$n=[with-key:<m>;k;2]><m/k>\s<n/k>"#, "1,2,3 1,2,x 1 2");
+}
+
+#[test]
+fn take_key_returns_value() {
+  test_rant!(r#"<$m=@(a=1;b=2)>[take-key:<m>;a]\s[is-empty:[take-key:<m>;a]]\s[keys-count:<m>]"#, "1 true 1");
+}
+
+#[test]
+fn entries_sorted_by_key() {
+  test_rant!(r#"[join:\s;[map:[entries:@(c=3;a=1;b=2)];[?:pair]{[join:"=";<pair>]}]]"#, "a=1 b=2 c=3");
+}
+
+#[test]
+fn deep_merge_nested_maps() {
+  test_rant!(r#"<$a=@(x=@(p=1;q=2);l=(1;2);s=a)><$b=@(x=@(q=3);l=(3);t=b)><$r=[deep-merge:<a>;<b>]><$c=[deep-merge:<a>;<b>;concat]><r/x/p><r/x/q>\s[join:,;<r/l>]\s[join:,;<c/l>]\s<r/s><r/t>\s<a/x/q>"#, "13 3 1,2,3 ab 2");
+}
+
+#[test]
+fn cond_sets_condval_and_returns() {
+  test_rant!(r#"<$a=[cond:[gt:3;2]]>{yes}[else]{no}\s<a>\s<$b=[cond:[gt:1;2]]>{yes}[else]{no}\s<b>"#, "yes true no false");
+}
+
+#[test]
+fn guard_returns_early() {
+  test_rant!(r#"[$f:x]{[guard:[gt:<x>;0]]positive}[f:1]\s[f:-1]end"#, "positive end");
+}
+
+#[test]
+fn guard_outside_function() {
+  test_rant_err!(r#"[guard:true]"#, RuntimeErrorType::ControlFlowError);
+}
+
+#[test]
+fn return_value_from_nested_blocks() {
+  test_rant!(r#"[$f]{a{b{c[return:[add:1;2]]d}e}f}[type:[f]]\s[f]"#, "integer 3");
+}
+
+#[test]
+fn return_from_repeater_unwinds_blocks() {
+  test_rant!(r#"[$f]{[rep:3]{[return:1]}}[rep:2][sep:-]{[f]x}"#, "1x-1x");
+}
+
+#[test]
+fn return_without_value_keeps_partial_output() {
+  test_rant!(r#"[$f]{before\s{[rep:3]{x[return]}}\safter}[f]"#, "before x");
+}
+
+#[test]
+fn spread_list_arguments() {
+  test_rant!(r#"<$args=(1;2)>[add:*<args>]\s[$f:a;b;c]{<a><b><c>}<$l=(x;y)>[f:*<l>;z]\s<$g=[?:a;b]{<b><a>}>[!<g>:*(1;2)]"#, "3 xyz 21");
+}
+
+#[test]
+fn spread_marker_keeps_deferred_blocks() {
+  test_rant!(r#"[resolve:*{a}]"#, "a");
+}
+
+#[test]
+fn spread_non_list_argument() {
+  test_rant_err!(r#"<$x=3>[add:*<x>;1]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn spread_marker_requires_list_expression() {
+  let r = Rant::new();
+  assert!(r.compile_quiet(r#"[upper:*bold*]"#).is_err());
+}
+
+#[test]
+fn named_arguments() {
+  test_rant!(r#"[$f:a;b?;c?]{<a>-<b>-<c>}[f:1;c=3]\s[f:c=z;a=x]"#, "1--3 x--z");
+}
+
+#[test]
+fn named_argument_unknown_param() {
+  test_rant_err!(r#"[$f:a;b?]{<a>}[f:1;z=2]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn named_argument_syntax_quoted_as_text() {
+  test_rant!(r#"[upper:"a=b"]\s[join:"x=y";(1;2)]"#, "A=B 1x=y2");
+}
+
+#[test]
+fn param_default_values() {
+  test_rant!(r#"[$f:a;b?=5]{<a>-<b>}[f:1]\s[f:1;2]\s[f:1;~]"#, "1-5 1-2 1-5");
+}
+
+#[test]
+fn param_default_value_uses_earlier_params() {
+  test_rant!(r#"[$f:a;b?=[add:<a>;1];c?=x]{<a>-<b>-<c>}[f:1]\s[f:1;c=y]"#, "1-2-x 1-2-y");
+}
+
+#[test]
+fn param_default_value_on_required_param() {
+  let r = Rant::new();
+  assert!(r.compile_quiet(r#"[$f:a=3]{<a>}"#).is_err());
+}
+
+#[test]
+fn new_from_proto() {
+  test_rant!(r#"<$p=@(greet=hi)><$o=[new:<p>;@(name=bob)]><o/name>\s[keys-count:<o>]\s[eq:[proto:<o>];<p>]\s[keys-count:[new:<p>]]"#, "bob 1 true 0");
+}
+
+#[test]
+fn proto_key_lookup() {
+  test_rant!(r#"<$a=@(x=1)><$b=[new:<a>]><$c=[new:<b>;@(y=2)]><c/x><c/y>\s[has:<c>;x]"#, "12 false");
+}
+
+#[test]
+fn proto_method_call() {
+  test_rant!(r#"<$P=@(greet=[?:self;x?=!]{Hi\s<self/name><x>})><$o=[new:<P>;@(name=Bob)]>[o/greet]\s[o/greet:?]"#, "Hi Bob! Hi Bob?");
+}
+
+#[test]
+fn own_function_call_has_no_self() {
+  test_rant!(r#"<$m=@(f=[?:a]{<a>})>[m/f:1]"#, "1");
+}
+
+#[test]
+fn bind_function_to_map() {
+  test_rant!(r#"<$o=@(name=Bob)><$g=[bind:<o>;[?:self;x]{<self/name><x>}]>[!<g>:!]\s<o/name=Alice>[!<g>:?]"#, "Bob! Alice?");
+}
+
+#[test]
+fn len_of_empty_and_non_empty() {
+  test_rant!(r#"[len:~]\s[len:abc]\s[non-empty:~]\s[non-empty:0]"#, "0 3 false true");
+}
+
+#[test]
+fn char_index_map() {
+  test_rant!(r#"<$m=[char-index-map:abca]><m/a><m/b><m/c>\s[keys-count:<m>]"#, "012 3");
+}
+
+#[test]
+fn rot_letters() {
+  test_rant!(r#"[rot:"Hello, World!";13]\s[rot:abz;-1]\s[rot:[rot:Zz;13];13]"#, "Uryyb, Jbeyq! zay Zz");
+}
+
+#[test]
+fn uuid_format() {
+  test_rant!(r#"<$id=[uuid]>[len:<id>]\s[matches-glob:<id>;"????????-????-4???-[89ab]???-????????????"]\s[neq:<id>;[uuid]]"#, "36 true true");
+}
+
+#[test]
+fn uuid_reproducible_with_seed() {
+  let mut r = Rant::with_seed(0xc0ffee);
+  let pgm = r.compile_quiet(r#"[uuid]"#).expect("failed to compile program");
+  let first = r.run_into_string(&pgm).expect("failed to run program");
+  r.reset_seed();
+  let second = r.run_into_string(&pgm).expect("failed to run program");
+  assert_eq!(first, second);
+}
+
+#[test]
+fn rgb_hex_conversion() {
+  test_rant!(r##"[rgb:255;128;-5]\s[rgb:300;0;15]\s[join:,;[hex-to-rgb:"#ff8000"]]\s[join:,;[hex-to-rgb:0fA]]"##, "#ff8000 #ff000f 255,128,0 0,255,170");
+}
+
+#[test]
+fn hex_to_rgb_invalid() {
+  test_rant_err!(r##"[hex-to-rgb:"#12345"]"##, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn trim_strings() {
+  test_rant!(r#"\[[trim:"  a b  "]\]\[[trim-start:"  a b  "]\]\[[trim-end:"  a b  "]\]\[[trim:"   "]\][trim:12]"#, "[a b][a b  ][  a b][]12");
+}
+
+#[test]
+fn random_color_bounds() {
+  test_rant!(r#"[random-color:hex;(0;0);(1;1)]\s[random-color:hex;(0;0);(0;0)]\s[random-color:hex;(0;0);(0.5;0.5)]\s[len:[random-color:rgb]]\s[len:[random-color]]"#, "#ffffff #000000 #808080 3 7");
+}
+
+#[test]
+fn lorem_word_count() {
+  test_rant!(r#"[count-words:[lorem:25]]\s[count-words:[lorem:25;2]]\s[count-lines:[lorem:25;2]]\s[len:[lorem:0]]"#, "25 25 3 0");
+}
+
+#[test]
+fn pow_and_sqrt() {
+  test_rant!(r#"[pow:2;10]\s[pow:2;-1]\s[pow:4;0.5]\s[type:[pow:2;3]]\s[type:[pow:2.0;3]]\s[pow:-1;9999999999]\s[sqrt:2.25]\s[type:[sqrt:16]]"#, "1024 0.5 2 integer float -1 1.5 float");
+}
+
+#[test]
+fn sqrt_negative() {
+  test_rant_err!(r#"[sqrt:-4]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn gen_name_custom_phonemes() {
+  test_rant!(r#"<$p=@(consonants=(x);vowels=(a))><$n=[gen-name:3;<p>]>\[[replace-map:[lower:<n>];@(x="";a="")]\]\s[count-substr:[lower:<n>];a]\s[len:[gen-name:0]]"#, "[] 3 0");
+}
+
+#[test]
+fn floor_ceil_round_keep_unrepresentable_floats() {
+  test_rant!(r#"[type:[floor:[float:inf]]]\s[type:[ceil:[float:NaN]]]\s[type:[round:[mul:10000000000.0;10000000000.0]]]\s[type:[round:-9000000000000000000.0]]"#, "float float float integer");
+}
+
+#[test]
+fn abs_floor_ceil_round() {
+  test_rant!(r#"[abs:-3]\s[abs:-2.5]\s[floor:2.7]\s[floor:-2.2]\s[ceil:2.1]\s[round:2.5]\s[round:-2.5]\s[round:7]\s[type:[floor:1.5]]\s[type:[abs:-1.5]]"#, "3 2.5 2 -3 3 3 -3 7 integer float");
+}
+
+#[test]
+fn alpha_weighted_letters() {
+  test_rant!(r#"[len:[alpha-weighted:12]]\s[len:[alpha-weighted]]\s[matches-glob:[alpha-weighted:5;fr];"[a-z][a-z][a-z][a-z][a-z]"]"#, "12 1 true");
+}
+
+#[test]
+fn reverse_list_and_string() {
+  test_rant!(r#"<$a=(1;2;3)>[join:,;[reverse:<a>]]\s[join:,;<a>]\s[reverse:"héllo"]"#, "3,2,1 1,2,3 olléh");
+}
+
+#[test]
+fn reverse_invalid_type() {
+  test_rant_err!(r#"[reverse:12]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn repeat_str() {
+  test_rant!(r#"[repeat-str:ab;5]\s\[[repeat-str:ab;0]\]"#, "ababababab []");
+}
+
+#[test]
+fn repeat_str_negative_count() {
+  test_rant_err!(r#"[repeat-str:ab;-1]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn markov_chain() {
+  test_rant!(r#"[markov:"a b c d.";6]\s[markov:"a b c d.";3;2]\s[count-words:[markov:"the cat sat. the dog sat on the cat.";25]]"#, "a b c d. a b a b c 25");
+}
+
+#[test]
+fn markov_corpus_too_short() {
+  test_rant_err!(r#"[markov:"a b";5;2]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn contains_membership() {
+  test_rant!(r#"<$l=(1;2;3)><$m=@(a=1;b=x)>[contains:<l>;3]\s[contains:<l>;4]\s[contains:"hello";ell]\s[contains:"hello";z]\s[contains:<m>;a]\s[contains:<m>;x]\s[contains:"a1b";1]"#, "true false true false true false true");
+}
+
+#[test]
+fn custom_selector_mode() {
+  let mut r = Rant::new();
+  r.register_selector_mode("skip-one", |count, _, last| last.map_or(0, |i| (i + 2) % count));
+  let pgm = r.compile_quiet(r#"<$s=[mksel:skip-one]>[rep:5][sel:<s>]{a|b|c}\s[rep:3][sel:forward]{x|y}"#).expect("failed to compile program");
+  assert_eq!(r.run_into_string(&pgm).unwrap(), "acbac xyx");
+}
+
+#[test]
+fn custom_selector_mode_out_of_range() {
+  let mut r = Rant::new();
+  r.register_selector_mode("bad", |count, _, _| count);
+  let pgm = r.compile_quiet(r#"[sel:bad]{a|b}"#).expect("failed to compile program");
+  assert_matches!(r.run_into_string(&pgm).map_err(|err| err.error_type), Err(RuntimeErrorType::SelectorError(_)));
+}
+
+#[test]
+fn sel_reset_restarts_forward_selector() {
+  test_rant!(r#"<$s=[mksel:forward]>[rep:2][sel:<s>]{a|b|c}[sel-reset:<s>][rep:2][sel:<s>]{a|b|c}"#, "abab");
+}
+
+#[test]
+fn sel_reset_non_selector() {
+  test_rant_err!(r#"[sel-reset:forward]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn starts_with_and_ends_with() {
+  test_rant!(r#"[starts-with:foobar;foo]\s[starts-with:foobar;bar]\s[starts-with:foo;]\s[ends-with:foobar;bar]\s[ends-with:foobar;foo]\s[ends-with:foo;]"#, "true false true true false true");
+}
+
+#[test]
+fn no_double_across_block_visits() {
+  let mut r = Rant::with_seed(0);
+  let pgm = r.compile_quiet(r#"[rep:50]{[no-double]{a|b}}"#).expect("failed to compile program");
+  let output = r.run_into_string(&pgm).expect("failed to run program");
+  assert_eq!(output.len(), 50);
+  assert!(!output.contains("aa") && !output.contains("bb"));
+}
+
+#[test]
+fn substring_counts_chars() {
+  test_rant!(r#"[substring:héllo wörld;1;4],[substring:héllo wörld;6],[substring:abc;5],[substring:abc;1;10],[substring:abc;-1]"#, "éllo,wörld,,bc,");
+}
+
+#[test]
+fn capitalize_keeps_rest() {
+  test_rant!(r#"[capitalize:mcDonald]\s[capitalize:élan]\s[capitalize:]"#, "McDonald Élan ");
+}
+
+#[test]
+fn title_case_words() {
+  test_rant!(r#"[title-case:"the quick  brown fox iPhone"]"#, "The Quick  Brown Fox IPhone");
+}
+
+#[test]
+fn map_output_applies_to_each_element() {
+  test_rant!(r#"[map-output:[?:s]{\(<s>\)}][rep:3][sep:\s]{a}"#, "(a) (a) (a)");
+}
+
+#[test]
+fn map_output_only_affects_next_block() {
+  test_rant!(r#"[map-output:[?:s]{[upper:<s>]}]{a{b}}{c}"#, "ABc");
+}
+
+#[test]
+fn negative_list_indices() {
+  test_rant!(r#"<$l=(a;b;c)><l/-1><l/-3><l/-1=z><l/2>"#, "caz");
+}
+
+#[test]
+fn negative_string_indices_count_chars() {
+  test_rant!(r#"<$s=héllo><s/-4><s/-1><s/1>"#, "éoé");
+}
+
+#[test]
+fn negative_index_out_of_range() {
+  test_rant_err!(r#"<$l=(a;b;c)><l/-4>"#, RuntimeErrorType::IndexError(_));
+}
+
+#[test]
+fn keyed_shuffle_ignores_prior_draws() {
+  let mut r = Rant::with_seed(42);
+  let plain = r.compile_quiet(r#"<$a=(1;2;3;4;5;6;7;8)>[shuffle:<a>;deck][join:,;<a>]"#).expect("failed to compile program");
+  let drawn = r.compile_quiet(r#"<$x=[rand:1;100]><$y=[randf:0;1]><$a=(1;2;3;4;5;6;7;8)>[shuffle:<a>;deck][join:,;<a>]"#).expect("failed to compile program");
+  let expected = r.run_into_string(&plain).expect("failed to run program");
+  assert_eq!(r.run_into_string(&drawn).expect("failed to run program"), expected);
+  assert_ne!(expected, "1,2,3,4,5,6,7,8");
+}
+
+#[test]
+fn keyed_shuffled_matches_keyed_shuffle() {
+  test_rant!(r#"<$a=(1;2;3;4;5;6;7;8)><$b=[shuffled:<a>;7]>[shuffle:<a>;7][eq:[join:,;<a>];[join:,;<b>]]"#, "true");
+}
+
+#[test]
+fn assert_approx_passes_within_epsilon() {
+  test_rant!(r#"[assert-approx:0.3;[add:0.1;0.2];0.000001]ok"#, "ok");
+}
+
+#[test]
+fn assert_approx_fails_outside_epsilon() {
+  test_rant_err!(r#"[assert-approx:1;1.5;0.1]"#, RuntimeErrorType::AssertError);
+}
+
+#[test]
+fn assert_type_reports_both_types() {
+  test_rant!(r#"[assert-type:5;integer]ok"#, "ok");
+  let mut r = Rant::new();
+  let pgm = r.compile_quiet(r#"[assert-type:5;string]"#).expect("failed to compile program");
+  let err = r.run_into_string(&pgm).unwrap_err();
+  assert!(err.description.contains("string") && err.description.contains("integer"));
+}
+
+#[test]
+fn slice_lists_and_strings() {
+  test_rant!(r#"[join:,;[slice:(1;2;3;4;5);1;3]]/[join:,;[slice:(1;2;3;4;5);-2]]/[len:[slice:(1;2;3);2;1]]/[slice:héllo;1;-1]/[slice:abc;-10;10]/[slice:abc;5]/"#, "2,3/4,5/0/éll/abc//");
+}
+
+#[test]
+fn sel_progress_cycles() {
+  test_rant!(r#"<$s=[mksel:forward]>[sel-progress:<s>][rep:5]{\s[sel:<s>]{a|b|c|d}=[sel-progress:<s>]}"#, "0 a=0.25 b=0.5 c=0.75 d=1 a=0.25");
+}
+
+#[test]
+fn sel_progress_random_is_empty() {
+  test_rant!(r#"<$r=[mksel:random]>[sel:<r>]{a}[is-empty:[sel-progress:<r>]]"#, "atrue");
+}
+
+#[test]
+fn fold_threads_accumulator() {
+  test_rant!(r#"[fold:(1;2;3;4);10;[?:acc;x]{[add:<acc>;<x>]}]\s[fold:(a;b;c);;[?:acc;x]{<x><acc>}]"#, "20 cba");
+}
+
+#[test]
+fn fold_empty_list_returns_init() {
+  test_rant!(r#"[fold:();init;[?:acc;x]{x}]"#, "init");
+}
+
+#[test]
+fn find_short_circuits() {
+  test_rant!(r#"<$n=0>[find:(1;2;3;4);[?:x]{<n=[add:<n>;1]>[gt:<x>;1]}]/<n>/[is-empty:[find:(1;2);[?:x]{[gt:<x>;5]}]]"#, "2/2/true");
+}
+
+#[test]
+fn find_index_returns_first_match_or_minus_one() {
+  test_rant!(r#"[find-index:(1;2;3;4);[?:x]{[gt:<x>;2]}]/[find-index:(1;2);[?:x]{[gt:<x>;5]}]"#, "2/-1");
+}
+
+#[test]
+fn flatten_map_joins_paths() {
+  test_rant!(r#"[join:,;[sorted:[keys:[flatten-map:@(a=@(b=1;c=(x;y));d=2)]]]]/[join:,;[sorted:[keys:[flatten-map:@(a=@(b=1));"::"]]]]"#, "a.b,a.c.0,a.c.1,d/a::b");
+}
+
+#[test]
+fn unflatten_map_restores_nesting() {
+  test_rant!(r#"<$u=[unflatten-map:[flatten-map:@(a=@(b=1;c=@(x=y)))]]><u/a/c/x><u/a/b>"#, "y1");
+}
+
+#[test]
+fn flatten_map_cyclic() {
+  test_rant_err!(r#"<$m=@(a=1)><m/self=<m>>[flatten-map:<m>]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn pick_deck_draws_without_replacement() {
+  let mut r = Rant::with_seed(7);
+  let pgm = r.compile_quiet(r#"<$d=[pick-deck:(a;b;c)]>[rep:10][sep:\s]{[d][d][d]}"#).expect("failed to compile program");
+  let output = r.run_into_string(&pgm).expect("failed to run program");
+  for round in output.split(' ') {
+    let mut picks: Vec<char> = round.chars().collect();
+    picks.sort_unstable();
+    assert_eq!(picks, vec!['a', 'b', 'c']);
+  }
+}
+
+#[test]
+fn pick_deck_empty_list() {
+  test_rant_err!(r#"[pick-deck:()]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn any_and_all_short_circuit() {
+  test_rant!(r#"<$n=0>[any:(1;5;3);[?:x]{<n=[add:<n>;1]>[gt:<x>;2]}]<n>/<$m=0>[all:(1;5;3);[?:x]{<m=[add:<m>;1]>[lt:<x>;2]}]<m>/[all:(1;2);[?:x]{[lt:<x>;3]}]"#, "true2/false2/true");
+}
+
+#[test]
+fn any_and_all_empty_list() {
+  test_rant!(r#"[any:();[?:x]{[eq:1;1]}]\s[all:();[?:x]{[eq:1;2]}]"#, "false true");
+}
+
+#[test]
+fn lerp_color_interpolates_channels() {
+  test_rant!(r##"[lerp-color:"#000000";"#ffffff";0.5]\s[lerp-color:"#ff0000";"#0000ff";0.25]\s[lerp-color:"#102030";"#fff";2]"##, "#808080 #bf0040 #ffffff");
+}
+
+#[test]
+fn lerp_color_invalid() {
+  test_rant_err!(r##"[lerp-color:"#00000g";"#ffffff";0.5]"##, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn flatten_nested_lists() {
+  test_rant!(r#"[join:,;[flatten:(1;(2;(3;(4;6)));();5)]]/[len:[flatten:(1;(2;(3;(4;6)));();5);1]]/[len:[flatten:(1;(2;3));0]]"#, "1,2,3,4,6,5/4/2");
+}
+
+#[test]
+fn flatten_cyclic_list() {
+  test_rant_err!(r#"<$l=(1;2)>[push:<l>;<l>][flatten:<l>]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn unique_keeps_first_occurrences() {
+  test_rant!(r#"[join:,;[unique:(b;a;b;1;c;a;1;1.5)]]/[len:[unique:()]]"#, "b,a,1,c,1.5/0");
+}
+
+#[test]
+fn dedup_removes_consecutive_duplicates() {
+  test_rant!(r#"[join:,;[dedup:(a;a;b;a;c;c;c)]]"#, "a,b,a,c");
+}
+
+#[test]
+fn here_prints_source_location() {
+  let mut r = Rant::with_options(RantOptions {
+    debug_mode: true,
+    .. Default::default()
+  });
+  let pgm = r.compile_quiet_named("a\n  [here]", "test").expect("failed to compile program");
+  assert_eq!(r.run_into_string(&pgm).expect("failed to run program"), "atest:2:3");
+}
+
+#[test]
+fn here_without_debug_info() {
+  test_rant!(r#"[here]"#, "");
+}
+
+#[test]
+fn chunk_splits_list() {
+  test_rant!(r#"<$c=[chunk:(1;2;3;4;5);2]>[len:<c>]:[join:,;<c/0>]:[join:,;<c/2>]"#, "3:1,2:5");
+}
+
+#[test]
+fn windows_overlap() {
+  test_rant!(r#"<$w=[windows:(1;2;3;4);3]>[len:<w>]:[join:,;<w/1>]:[len:[windows:(1;2);3]]"#, "2:2,3,4:0");
+}
+
+#[test]
+fn chunk_size_zero() {
+  test_rant_err!(r#"[chunk:(1;2);0]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn is_cyclic_detects_self_reference() {
+  test_rant!(r#"<$l=(1;2)>[is-cyclic:<l>][push:<l>;<l>]\s[is-cyclic:<l>]\s<$m=@(a=@(b=1))><m/a/up=<m>>[is-cyclic:<m>]"#, "false true true");
+}
+
+#[test]
+fn is_cyclic_shared_values_are_not_cycles() {
+  test_rant!(r#"<$a=(1)><$b=(<a>;<a>)>[is-cyclic:<b>]\s[is-cyclic:5]"#, "false false");
+}
+
+#[test]
+fn copy_cyclic_map() {
+  test_rant_err!(r#"<$m=@(a=@(b=1))><m/a/up=<m>>[copy:<m>]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn weighted_sample_without_replacement() {
+  test_rant!(r#"[join:,;[sorted:[weighted-sample:(a;b;c;d);(1;0;2;3);3]]]"#, "a,c,d");
+}
+
+#[test]
+fn weighted_sample_with_replacement() {
+  test_rant!(r#"[join:,;[weighted-sample:(a;b);(0;1);5;true]]"#, "b,b,b,b,b");
+}
+
+#[test]
+fn weighted_sample_mixed_magnitude_weights() {
+  test_rant!(r#"[rep:10][sep:/]{[join:,;[sorted:[weighted-sample:(a;b;c);(10000000000000000.0;1;1);3]]]}"#, "a,b,c/a,b,c/a,b,c/a,b,c/a,b,c/a,b,c/a,b,c/a,b,c/a,b,c/a,b,c");
+}
+
+#[test]
+fn weighted_sample_too_few_items() {
+  test_rant_err!(r#"[weighted-sample:(a;b);(0;1);2]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn weighted_sample_weight_count_mismatch() {
+  test_rant_err!(r#"[weighted-sample:(a;b);(1);1]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn range_counts_up_and_down() {
+  test_rant!(r#"[join:,;[range:0;4]]/[join:,;[range:1;10;3]]/[join:,;[range:5;0;-2]]/[len:[range:0;5;-1]]"#, "0,1,2,3/1,4,7/5,3,1/0");
+}
+
+#[test]
+fn range_zero_step() {
+  test_rant_err!(r#"[range:0;5;0]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn fdiv_returns_float() {
+  test_rant!(r#"[div:1;2]\s[fdiv:1;2]\s[fdiv:7;2]\s[type:[fdiv:4;2]]\s[fdiv:1.5;0.5]"#, "0 0.5 3.5 float 3");
+}
+
+#[test]
+fn fdiv_by_zero() {
+  test_rant_err!(r#"[fdiv:1;0]"#, RuntimeErrorType::ValueError(_));
+}
+
+#[test]
+fn divmod_truncates() {
+  test_rant!(r#"[join:,;[divmod:125;60]]/[join:,;[divmod:-7;2]]/[join:,;[divmod:7.5;2]]"#, "2,5/-3,-1/3,1.5");
+}
+
+#[test]
+fn divmod_by_zero() {
+  test_rant_err!(r#"[divmod:5;0]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn mean_and_median() {
+  test_rant!(r#"[mean:(1;2;3;6)]/[type:[mean:(2;2)]]/[median:(3;1;2)]/[median:(4;1;2;3)]"#, "3/float/2/2.5");
+}
+
+#[test]
+fn mean_empty_list() {
+  test_rant_err!(r#"[mean:()]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn median_non_numeric() {
+  test_rant_err!(r#"[median:(1;a;2)]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn cap_string_and_list() {
+  test_rant!(r#"[cap:héllo;3]/[cap:hi;5]/[join:,;[cap:(1;2;3;4);2]]/[len:[cap:(1;2);0]]"#, "hél/hi/1,2/0");
+}
+
+#[test]
+fn cap_negative_max() {
+  test_rant_err!(r#"[cap:hello;-1]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn weighted_selector_skips_zero_weights() {
+  test_rant!(r#"<$s=[mksel:weighted]>[rep:8]{[sel:<s>][weight:0][weight:1][weight:0]{a|b|c}}"#, "bbbbbbbb");
+}
+
+#[test]
+fn weighted_selector_default_weight() {
+  test_rant!(r#"[rep:8]{[sel:weighted][weight:0]{a|b}}"#, "bbbbbbbb");
+}
+
+#[test]
+fn weighted_selector_zero_total_weight() {
+  test_rant_err!(r#"[sel:weighted][weight:0][weight:0]{a|b}"#, RuntimeErrorType::SelectorError(_));
+}
+
+#[test]
+fn weight_negative() {
+  test_rant_err!(r#"[weight:-1]{a}"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn randf_normal_distribution() {
+  test_rant!(r#"<$xs=()>[rep:4000]{[push:<xs>;[randf-normal:10;2]]}[assert-approx:[mean:<xs>];10;0.2][randf-normal:5;0]"#, "5");
+}
+
+#[test]
+fn randf_normal_clamped_in_range() {
+  test_rant!(r#"[rep:200]{[assert:[is-between:[randf-normal-clamped:0;10;-1;1];-1;1]]}"#, "");
+}
+
+#[test]
+fn randf_normal_reproducible_with_seed() {
+  let mut r = Rant::with_seed(0xc0ffee);
+  let pgm = r.compile_quiet(r#"[randf-normal:0;1]"#).expect("failed to compile program");
+  let first = r.run_into_string(&pgm).expect("failed to run program");
+  r.reset_seed();
+  let second = r.run_into_string(&pgm).expect("failed to run program");
+  assert_eq!(first, second);
+}
+
+#[test]
+fn randf_normal_negative_stddev() {
+  test_rant_err!(r#"[randf-normal:0;-1]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn reseed_restarts_random_stream() {
+  test_rant!(r#"<$a=[rand:1;1000000]>[reseed:42]<$b=[rand:1;1000000]><$c=[rand:1;1000000]>[reseed:42][eq:<b>;[rand:1;1000000]]/[seed]"#, "true/42");
+}
+
+#[test]
+fn reseed_with_same_seed_as_new_context() {
+  let mut r = Rant::with_seed(0xc0ffee);
+  let pgm = r.compile_quiet(r#"[rand:1;1000000]"#).expect("failed to compile program");
+  let expected = r.run_into_string(&pgm).expect("failed to run program");
+  let mut r = Rant::new();
+  let pgm = r.compile_quiet(r#"[rand:1;1000000][reseed:12648430][rand:1;1000000]"#).expect("failed to compile program");
+  let output = r.run_into_string(&pgm).expect("failed to run program");
+  assert!(output.ends_with(expected.as_str()));
+}
+
+#[test]
+fn min_max_list() {
+  test_rant!(r#"[join:,;[min-max:(3;-1;4;1;5;9;2)]]/[join:,;[min-max:(7)]]"#, "-1,9/7,7");
+}
+
+#[test]
+fn min_max_empty_list() {
+  test_rant_err!(r#"[min-max:()]"#, RuntimeErrorType::ArgumentError);
+}
+
+#[test]
+fn memoize_distinguishes_argument_contents_and_types() {
+  test_rant!(r#"<$f=[memoize:<sum>]>[f:(1;2)],[f:(3;4)],[f:(1;2)]/<$g=[memoize:[?:m]{<m/a>}]>[g:@(a=1)],[g:@(a=2)]/<$t=[memoize:[?:x]{[type:<x>]}]>[t:1],[t:"1"]"#, "3,7,3/1,2/integer,string");
 }
\ No newline at end of file